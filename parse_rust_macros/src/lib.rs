@@ -0,0 +1,142 @@
+//! `parse_format!` checks a `parse_rust` format string while compiling, so a
+//! typo such as an unknown type or an unbalanced brace is a compile error
+//! instead of a runtime `ParseError::InvalidFormat`.
+//!
+//! ```
+//! use parse_rust_macros::parse_format;
+//!
+//! let parser = parse_format!("Value is {:d}").unwrap();
+//! assert!(parser.parse("VALUE IS 42").is_some());
+//! let parser = parse_format!("Value is {:d}", true).unwrap();
+//! assert!(parser.parse("VALUE IS 42").is_none());
+//! ```
+//!
+//! The errors reported for bad formats are checked in `tests/ui/fail`.
+//!
+//! Only the built-in types are known here, so formats using custom types
+//! still need `Parser::new_with_types`. Types from `parse_rust`'s optional
+//! features (`b64`) are known when the same feature is enabled on this crate.
+
+use parse_rust::Parser;
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+// `parse_format!("format")` or `parse_format!("format", case_sensitive)`,
+// expanding to `Parser::new(format, case_sensitive)`. Like the crate's free
+// `parse` and `search`, formats are case insensitive unless `true` is given.
+#[proc_macro]
+pub fn parse_format(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err((message, span)) => compile_error(&message, span),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, (String, Span)> {
+    let mut tokens = input.into_iter();
+    let Some(TokenTree::Literal(literal)) = tokens.next() else {
+        return Err((
+            "expected a format string literal".to_string(),
+            Span::call_site(),
+        ));
+    };
+    let format = string_value(&literal.to_string()).ok_or_else(|| {
+        (
+            "expected a format string literal".to_string(),
+            literal.span(),
+        )
+    })?;
+
+    let case_sensitive = match (tokens.next(), tokens.next()) {
+        (None, _) => false,
+        (Some(TokenTree::Punct(comma)), None) if comma.as_char() == ',' => false,
+        (Some(TokenTree::Punct(comma)), Some(TokenTree::Ident(flag)))
+            if comma.as_char() == ',' && matches!(flag.to_string().as_str(), "true" | "false") =>
+        {
+            flag.to_string() == "true"
+        }
+        (_, Some(token)) | (Some(token), None) => {
+            return Err(("expected `true` or `false`".to_string(), token.span()));
+        }
+    };
+    if let Some(TokenTree::Punct(comma)) = tokens.next() {
+        if comma.as_char() != ',' {
+            return Err(("unexpected token".to_string(), comma.span()));
+        }
+    }
+    if let Some(token) = tokens.next() {
+        return Err(("unexpected token".to_string(), token.span()));
+    }
+
+    if let Err(error) = Parser::new(&format, case_sensitive) {
+        return Err((format!("{}: {:?}", error, format), literal.span()));
+    }
+
+    let output = format!("::parse_rust::Parser::new({}, {})", literal, case_sensitive);
+    Ok(output.parse().unwrap())
+}
+
+// The value of a (possibly raw) string literal, from its source text
+fn string_value(source: &str) -> Option<String> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        return Some(
+            body.strip_suffix(&format!("\"{}", "#".repeat(hashes)))?
+                .to_string(),
+        );
+    }
+
+    let body = source.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '\'' => value.push('\''),
+            '"' => value.push('"'),
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                value.push(char::from(u8::from_str_radix(&digits, 16).ok()?));
+            }
+            'u' => {
+                chars.next().filter(|&c| c == '{')?;
+                let digits: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let digits = digits.replace('_', "");
+                value.push(char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?);
+            }
+            // A line continuation skips the newline and leading whitespace
+            '\n' => {
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+// `compile_error!("message")`, reported at `span`
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(message).into());
+    group.set_span(span);
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(group),
+    ]
+    .into_iter()
+    .collect()
+}
@@ -0,0 +1,15 @@
+// Formats the macro accepts must compile, and typos must fail with the
+// messages recorded next to each case in `tests/ui/fail`
+#[test]
+fn test_compile_checks() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
+
+// Types from a forwarded `parse_rust` feature are known to the macro
+#[cfg(feature = "base64")]
+#[test]
+fn test_feature_types() {
+    trybuild::TestCases::new().pass("tests/ui/pass_base64/*.rs");
+}
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod tests {
+    use parse_rust_macros::parse_format;
+
+    #[test]
+    fn test_parse_format_macro() {
+        let parser = parse_format!("Name: {name:w}, Age: {age:d}").unwrap();
+        let result = parser.parse("Name: John, Age: 30").unwrap();
+        assert_eq!(result.named::<i64>("age"), Some(&30));
+
+        let parser = parse_format!(r"Ticket {id:/[A-Z]{3}-\d+/}", false).unwrap();
+        assert!(parser.parse("TICKET ABC-1").is_some());
+
+        let parser = parse_format!("tab\t{:d}\u{2192}{:w}",).unwrap();
+        let result = parser.parse("tab\t1\u{2192}x").unwrap();
+        assert_eq!(result.get::<i64>(0), Some(&1));
+    }
+}
@@ -0,0 +1,5 @@
+use parse_rust_macros::parse_format;
+
+fn main() {
+    let _parser = parse_format!("Value is {:d}", yes);
+}
@@ -0,0 +1,6 @@
+use parse_rust_macros::parse_format;
+
+fn main() {
+    let format = "Value is {:d}";
+    let _parser = parse_format!(format);
+}
@@ -0,0 +1,12 @@
+use parse_rust_macros::parse_format;
+
+fn main() {
+    let parser = parse_format!("Value is {:d}").unwrap();
+    assert!(parser.parse("VALUE IS 42").is_some());
+
+    let parser = parse_format!("Value is {:d}", true).unwrap();
+    assert!(parser.parse("VALUE IS 42").is_none());
+
+    let parser = parse_format!(r"{user:w} at {when:ti}",).unwrap();
+    assert!(parser.parse("alice at 2024-12-27T19:57:55").is_some());
+}
@@ -0,0 +1,7 @@
+use parse_rust_macros::parse_format;
+
+// Only the compile-time check is exercised: the `parse_rust` this test
+// links against is built without `base64`, so the parser would fail to build
+fn main() {
+    let _parser = parse_format!("token={:b64}");
+}
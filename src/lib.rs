@@ -1,7 +1,12 @@
-use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use regex::{Regex, RegexBuilder};
-use std::any::Any;
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::num::{IntErrorKind, NonZeroU64};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use thiserror::Error;
 
 #[derive(Debug)]
@@ -10,15 +15,201 @@ pub struct Parser {
     search_re: Regex,
     field_map: HashMap<String, usize>,
     field_types: HashMap<String, String>,
-    type_converters: HashMap<String, Box<dyn TypeConverter>>,
+    // Fields from a `( {arg})*` group, matched again one repetition at a time
+    repeated: HashMap<String, Regex>,
+    // Text converted in place of an optional `{port:d=8080}` field that is absent
+    defaults: HashMap<String, String>,
+    // The pattern up to the end of each field, for `debug_match`
+    field_prefixes: Vec<String>,
+    case_modifiers: HashMap<String, CaseModifier>,
+    // `{close=open}` fields, which must match the same text as the named field
+    references: HashMap<String, String>,
+    // Groups of later `{tag}` occurrences, which must match what the first did
+    backreferences: Vec<(usize, String)>,
+    // `{entries:[{k:w}={v:w}]}` fields, whose text is searched with a sub-format
+    sub_parsers: HashMap<String, Parser>,
+    // Set when the format, or one of its `{|}` branches, ends in an untyped field
+    trailing_untyped: bool,
+    type_converters: Arc<HashMap<String, Box<dyn TypeConverter>>>,
+    case_sensitive: bool,
+    // Set for field-less formats that can be matched by plain comparison
+    literal: Option<String>,
+    conversion_cache: Option<Mutex<ConversionCache>>,
+    isolated_numbers: bool,
+    normalize_whitespace: bool,
+    absent_as_empty: bool,
 }
 
+type CacheKey = (String, String);
+
+// Least-recently-used map from (type key, raw text) to the converted value
+type ConversionCache = LruCache<CacheKey, Arc<dyn Any + Send + Sync>>;
+
+// Map holding at most `capacity` entries, evicting the least recently used.
+// Entries live in a slab linked from most to least recently used, so hits
+// and evictions take constant time.
 #[derive(Debug)]
+struct LruCache<K, V> {
+    capacity: usize,
+    slots: HashMap<K, usize>,
+    entries: Vec<CacheEntry<K, V>>,
+    newest: Option<usize>,
+    oldest: Option<usize>,
+}
+
+#[derive(Debug)]
+struct CacheEntry<K, V> {
+    key: K,
+    value: V,
+    newer: Option<usize>,
+    older: Option<usize>,
+}
+
+impl<K: std::hash::Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            slots: HashMap::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+            newest: None,
+            oldest: None,
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        let slot = *self.slots.get(key)?;
+        self.unlink(slot);
+        self.push_newest(slot);
+        Some(self.entries[slot].value.clone())
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.entries.clear();
+        self.newest = None;
+        self.oldest = None;
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        // Another conversion of the same text may have been cached meanwhile
+        if let Some(&slot) = self.slots.get(&key) {
+            self.entries[slot].value = value;
+            self.unlink(slot);
+            self.push_newest(slot);
+            return;
+        }
+
+        let slot = if self.entries.len() < self.capacity {
+            self.entries.push(CacheEntry {
+                key: key.clone(),
+                value,
+                newer: None,
+                older: None,
+            });
+            self.entries.len() - 1
+        } else {
+            // Reuse the least recently used entry's slot
+            let slot = self.oldest.unwrap();
+            self.unlink(slot);
+            let entry = &mut self.entries[slot];
+            self.slots.remove(&entry.key);
+            entry.key = key.clone();
+            entry.value = value;
+            slot
+        };
+        self.slots.insert(key, slot);
+        self.push_newest(slot);
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (newer, older) = (self.entries[slot].newer, self.entries[slot].older);
+        match newer {
+            Some(newer) => self.entries[newer].older = older,
+            None => self.newest = older,
+        }
+        match older {
+            Some(older) => self.entries[older].newer = newer,
+            None => self.oldest = newer,
+        }
+    }
+
+    fn push_newest(&mut self, slot: usize) {
+        self.entries[slot].newer = None;
+        self.entries[slot].older = self.newest;
+        match self.newest {
+            Some(newest) => self.entries[newest].newer = Some(slot),
+            None => self.oldest = Some(slot),
+        }
+        self.newest = Some(slot);
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ParseResult {
-    converted: Vec<Box<dyn Any>>,
+    // Shared so results and individual values can be cloned cheaply
+    converted: Vec<Value>,
+    raw: Vec<String>,
+    field_map: HashMap<String, usize>,
+    span: (usize, usize),
+    // `None` for fields that took no text from the input
+    field_spans: Vec<Option<(usize, usize)>>,
+    type_keys: Vec<Option<String>>,
+}
+
+// A result holding only the matched text of each field, which unlike
+// `ParseResult` is `Send`. Comparable and cheap to store in bulk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedResult {
+    raw: Vec<String>,
     field_map: HashMap<String, usize>,
 }
 
+impl OwnedResult {
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.raw.get(index).map(String::as_str)
+    }
+
+    pub fn named(&self, name: &str) -> Option<&str> {
+        self.field_map.get(name).and_then(|&index| self.get(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+}
+
+// A converted value, held in an `Arc` when its converter implements
+// `convert_shared` and in an `Rc` otherwise
+#[derive(Debug, Clone)]
+enum Value {
+    Shared(Arc<dyn Any + Send + Sync>),
+    Local(Rc<dyn Any>),
+}
+
+impl Value {
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        match self {
+            Value::Shared(value) => value.downcast_ref::<T>(),
+            Value::Local(value) => value.downcast_ref::<T>(),
+        }
+    }
+
+    fn is<T: 'static>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+}
+
 impl ParseResult {
     pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
         self.converted
@@ -26,8 +217,18 @@ impl ParseResult {
             .and_then(|value| value.downcast_ref::<T>())
     }
 
+    // Position of a named field. A dotted name such as `user.name` can also
+    // be written `user__name`, and the other way round.
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.field_map
+            .get(name)
+            .or_else(|| self.field_map.get(&name.replace("__", ".")))
+            .or_else(|| self.field_map.get(&name.replace('.', "__")))
+            .copied()
+    }
+
     pub fn named<T: 'static>(&self, name: &str) -> Option<&T> {
-        if let Some(&index) = self.field_map.get(name) {
+        if let Some(index) = self.index_of(name) {
             self.converted
                 .get(index)
                 .and_then(|value| value.downcast_ref::<T>())
@@ -35,8 +236,223 @@ impl ParseResult {
             None
         }
     }
+
+    // A value from a converter with `convert_shared`, which can be kept past
+    // the result or sent to another thread
+    pub fn get_arc<T: Send + Sync + 'static>(&self, index: usize) -> Option<Arc<T>> {
+        match self.converted.get(index)? {
+            Value::Shared(value) => Arc::clone(value).downcast::<T>().ok(),
+            Value::Local(_) => None,
+        }
+    }
+
+    pub fn named_arc<T: Send + Sync + 'static>(&self, name: &str) -> Option<Arc<T>> {
+        self.get_arc(self.index_of(name)?)
+    }
+
+    // The records a `{entries:[...]}` field's sub-format found in its text
+    pub fn records(&self, name: &str) -> Option<&[ParseResult]> {
+        self.named::<Vec<ParseResult>>(name).map(Vec::as_slice)
+    }
+
+    // Move every span `by` bytes later, for results of a sub-format
+    fn offset_spans(&mut self, by: usize) {
+        self.span = (self.span.0 + by, self.span.1 + by);
+        for span in self.field_spans.iter_mut().flatten() {
+            *span = (span.0 + by, span.1 + by);
+        }
+    }
+
+    // Convert a field's matched text again with a different converter
+    pub fn reinterpret<T: 'static>(&self, name: &str, converter: &dyn TypeConverter) -> Option<T> {
+        let raw = self.raw.get(self.index_of(name)?)?;
+        let value = converter.convert(raw).ok()?;
+        value.downcast::<T>().ok().map(|value| *value)
+    }
+
+    pub fn get_range<T: Clone + 'static>(&self, range: std::ops::Range<usize>) -> Option<Vec<T>> {
+        range.map(|index| self.get::<T>(index).cloned()).collect()
+    }
+
+    // Byte offsets of the whole match within the input
+    pub fn match_span(&self) -> (usize, usize) {
+        self.span
+    }
+
+    // The matched text, or `None` if `input` is not the text that was parsed
+    pub fn matched_text<'a>(&self, input: &'a str) -> Option<&'a str> {
+        input.get(self.span.0..self.span.1)
+    }
+
+    // Byte offsets of each field within the input, in positional order;
+    // `None` for a field that didn't match any text (see `spans_of`)
+    pub fn spans(&self) -> &[Option<(usize, usize)>] {
+        &self.field_spans
+    }
+
+    // Byte offsets of the text a field matched, or `None` if it didn't match
+    // any (a defaulted or `absent_as_empty` field)
+    pub fn spans_of(&self, index: usize) -> Option<(usize, usize)> {
+        self.field_spans.get(index).copied().flatten()
+    }
+
+    pub fn named_span(&self, name: &str) -> Option<(usize, usize)> {
+        self.spans_of(self.index_of(name)?)
+    }
+
+    // The text a field matched, sliced out of `input` without manual indexing;
+    // `None` for a field that didn't match any text
+    pub fn get_slice<'a>(&self, input: &'a str, index: usize) -> Option<&'a str> {
+        let (start, end) = self.spans_of(index)?;
+        input.get(start..end)
+    }
+
+    pub fn field_slice<'a>(&self, input: &'a str, name: &str) -> Option<&'a str> {
+        self.get_slice(input, self.index_of(name)?)
+    }
+
+    // The type key each positional field was converted with, `None` if untyped
+    pub fn type_keys(&self) -> Vec<Option<&str>> {
+        self.type_keys.iter().map(Option::as_deref).collect()
+    }
+
+    // Clone the positional values into a typed tuple, e.g. `(i64, String)`
+    pub fn try_into_tuple<T: FromParseResult>(&self) -> Result<T, ParseError> {
+        T::from_parse_result(self)
+    }
+
+    // Named fields' values, written as by `to_json` and nested on the dots
+    // in their names. A field whose
+    // name is also the prefix of another (`user` and `user.id`) is replaced
+    // by the nested object.
+    #[cfg(feature = "serde")]
+    pub fn nested_map(&self) -> serde_json::Value {
+        use serde_json::{Map, Value};
+
+        let mut root = Map::new();
+        let mut names: Vec<(&String, &usize)> = self.field_map.iter().collect();
+        names.sort_by_key(|&(_, &index)| index);
+
+        for (name, &index) in names {
+            if name.parse::<usize>().is_ok() {
+                continue;
+            }
+            let mut parts: Vec<&str> = name.split('.').collect();
+            let leaf = parts.pop().unwrap();
+            let mut map = &mut root;
+            for part in parts {
+                let entry = map.entry(part).or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                let Value::Object(inner) = entry else {
+                    unreachable!()
+                };
+                map = inner;
+            }
+            if !map.get(leaf).is_some_and(Value::is_object) {
+                let value = serde_json::to_value(FieldValue(self, index))
+                    .expect("field values serialize to JSON");
+                map.insert(leaf.to_string(), value);
+            }
+        }
+        Value::Object(root)
+    }
+
+    // A JSON object of the fields in positional order (see the `Serialize`
+    // impl)
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("field names are strings")
+    }
+
+    // The matched text of every positional field, in order
+    pub fn into_vec(self) -> Vec<String> {
+        self.raw
+    }
+
+    pub fn into_owned(self) -> OwnedResult {
+        OwnedResult {
+            raw: self.raw,
+            field_map: self.field_map,
+        }
+    }
 }
 
+// A JSON object of the fields in positional order, keyed by name (or
+// position for unnamed fields). Integers and finite floats are written as
+// numbers, sub-format records as arrays, everything else as the matched text.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseResult {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut names: Vec<(&String, &usize)> = self.field_map.iter().collect();
+        names.sort_by_key(|&(_, &index)| index);
+
+        let mut map = serializer.serialize_map(Some(names.len()))?;
+        for (name, &index) in names {
+            map.serialize_entry(name, &FieldValue(self, index))?;
+        }
+        map.end()
+    }
+}
+
+// The field at an index of a result, serialized as described above
+#[cfg(feature = "serde")]
+struct FieldValue<'a>(&'a ParseResult, usize);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldValue<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let FieldValue(result, index) = *self;
+        let value = &result.converted[index];
+        if let Some(n) = value.downcast_ref::<i64>() {
+            serializer.serialize_i64(*n)
+        } else if let Some(n) = value.downcast_ref::<f64>().filter(|n| n.is_finite()) {
+            serializer.serialize_f64(*n)
+        } else if let Some(records) = value.downcast_ref::<Vec<ParseResult>>() {
+            records.serialize(serializer)
+        } else {
+            serializer.serialize_str(&result.raw[index])
+        }
+    }
+}
+
+// A piece of the input returned by `findall_with_gaps`
+#[derive(Debug, Clone)]
+pub enum Segment<'a> {
+    // Text between matches
+    Text(&'a str),
+    Match(ParseResult),
+}
+
+pub trait FromParseResult: Sized {
+    fn from_parse_result(result: &ParseResult) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_parse_result {
+    ($($index:tt: $t:ident),+) => {
+        impl<$($t: Clone + 'static),+> FromParseResult for ($($t,)+) {
+            fn from_parse_result(result: &ParseResult) -> Result<Self, ParseError> {
+                Ok(($(
+                    result
+                        .get::<$t>($index)
+                        .cloned()
+                        .ok_or(ParseError::TypeMismatch($index))?,
+                )+))
+            }
+        }
+    };
+}
+
+impl_from_parse_result!(0: A);
+impl_from_parse_result!(0: A, 1: B);
+impl_from_parse_result!(0: A, 1: B, 2: C);
+impl_from_parse_result!(0: A, 1: B, 2: C, 3: D);
+impl_from_parse_result!(0: A, 1: B, 2: C, 3: D, 4: E);
+impl_from_parse_result!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F);
+
 #[derive(Error, Debug)]
 pub enum ParseError {
     #[error("invalid format string")]
@@ -45,63 +461,584 @@ pub enum ParseError {
     NoMatch,
     #[error("type conversion failed")]
     TypeConversionFailed,
+    #[error("integer overflow: {0}")]
+    IntegerOverflow(String),
+    #[error("field {0} is missing or has a different type")]
+    TypeMismatch(usize),
+    #[error("untyped trailing field is ambiguous in strict mode")]
+    AmbiguousTrailingField,
+    #[error("converted value cannot be shared between threads")]
+    NotShareable,
 }
 
 // Type conversion traits
-pub trait TypeConverter: Send + Sync + std::fmt::Debug {
+pub trait TypeConverter: CloneConverter + Send + Sync + std::fmt::Debug {
     fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError>;
+    // `convert` for converters whose values are `Send + Sync`. Results keep
+    // these values in an `Arc`, so they can be handed out by `get_arc` and
+    // reused by the conversion cache; values from converters that only
+    // implement `convert` stay local to their result. Such converters can
+    // implement `convert` with `convert_via_shared!()`.
+    fn convert_shared(&self, _s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        Err(ParseError::NotShareable)
+    }
     fn get_pattern(&self) -> Option<&str> {
         None
     }
+    // The type boxed by `convert`, if it is always the same
+    fn output_type_id(&self) -> Option<TypeId> {
+        None
+    }
+    // Whether a field of this type may contain whitespace. Only consulted
+    // when `get_pattern` is `None`: such a field matches any text if this
+    // is true, and a run of non-whitespace otherwise.
+    fn is_whitespace_sensitive(&self) -> bool {
+        true
+    }
+}
+
+// Implements `TypeConverter::convert` by boxing the value from `convert_shared`
+#[macro_export]
+macro_rules! convert_via_shared {
+    () => {
+        fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, $crate::ParseError> {
+            self.convert_shared(s)
+                .map(|value| value as Box<dyn std::any::Any>)
+        }
+    };
+}
+
+// Lets boxed converters be cloned; implemented for every `Clone` converter
+pub trait CloneConverter {
+    fn clone_box(&self) -> Box<dyn TypeConverter>;
+}
+
+impl<T: TypeConverter + Clone + 'static> CloneConverter for T {
+    fn clone_box(&self) -> Box<dyn TypeConverter> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn TypeConverter> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
 // Built-in type converters
 #[derive(Debug, Clone)]
 pub struct IntConverter;
 impl TypeConverter for IntConverter {
-    fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
         s.parse::<i64>()
-            .map(|n| Box::new(n) as Box<dyn std::any::Any>)
-            .map_err(|_| ParseError::TypeConversionFailed)
+            .map(|n| Box::new(n) as Box<dyn std::any::Any + Send + Sync>)
+            .map_err(|e| match e.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                    ParseError::IntegerOverflow(s.to_string())
+                }
+                _ => ParseError::TypeConversionFailed,
+            })
     }
 
     fn get_pattern(&self) -> Option<&str> {
         Some(r"-?\d+")
     }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
+    }
+}
+
+// The `d` type when `scientific_integers` is enabled: also accepts
+// integer-valued scientific notation such as `1e3`
+#[derive(Debug, Clone)]
+pub struct ScientificIntConverter;
+impl TypeConverter for ScientificIntConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        if !s.contains(['e', 'E']) {
+            return IntConverter.convert_shared(s);
+        }
+        let value = s
+            .parse::<f64>()
+            .map_err(|_| ParseError::TypeConversionFailed)?;
+        if value.fract() != 0.0 {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        if value < i64::MIN as f64 || value >= i64::MAX as f64 {
+            return Err(ParseError::IntegerOverflow(s.to_string()));
+        }
+        Ok(Box::new(value as i64))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?\d+(?:(?:\.\d+)?[eE][-+]?\d+)?")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
+    }
+}
+
+// Hex byte pairs, optionally space separated, decoded into a `Vec<u8>`
+#[derive(Debug, Clone)]
+pub struct HexBytesConverter;
+impl TypeConverter for HexBytesConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+        if !digits.len().is_multiple_of(2) {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| {
+                std::str::from_utf8(pair)
+                    .ok()
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .ok_or(ParseError::TypeConversionFailed)
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        Ok(Box::new(bytes))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        // No trailing space, so a following literal space still matches
+        Some(r"[0-9a-fA-F]{2}(?:\s?[0-9a-fA-F]{2})*")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<Vec<u8>>())
+    }
+}
+
+// An ordinal such as `3rd`, returned as its `i64` value. The suffix must
+// agree with the number (`11th`, not `11st`).
+#[derive(Debug, Clone)]
+pub struct OrdinalConverter;
+impl TypeConverter for OrdinalConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let lower = s.to_ascii_lowercase();
+        let (digits, suffix) = ["st", "nd", "rd", "th"]
+            .into_iter()
+            .find_map(|suffix| Some((lower.strip_suffix(suffix)?, suffix)))
+            .ok_or(ParseError::TypeConversionFailed)?;
+        let n = digits.parse::<i64>().map_err(|e| match e.kind() {
+            IntErrorKind::PosOverflow => ParseError::IntegerOverflow(s.to_string()),
+            _ => ParseError::TypeConversionFailed,
+        })?;
+        let expected = match (n % 10, n % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        if suffix != expected {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        Ok(Box::new(n))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"\d+(?:st|nd|rd|th)")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
+    }
+}
+
+// An ISO date optionally preceded by its weekday, e.g. `Monday, 2024-12-30`;
+// when the weekday is given it must be the date's actual weekday
+#[derive(Debug, Clone)]
+pub struct WeekdayDateConverter;
+impl TypeConverter for WeekdayDateConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let split = s.len().saturating_sub(10);
+        let (Some(weekday), Some(date)) = (s.get(..split), s.get(split..)) else {
+            return Err(ParseError::TypeConversionFailed);
+        };
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ParseError::TypeConversionFailed)?;
+        let weekday = weekday.trim_end().trim_end_matches(',');
+        if !weekday.is_empty() {
+            let weekday = weekday
+                .parse::<chrono::Weekday>()
+                .map_err(|_| ParseError::TypeConversionFailed)?;
+            if chrono::Datelike::weekday(&date) != weekday {
+                return Err(ParseError::TypeConversionFailed);
+            }
+        }
+        Ok(Box::new(date))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"(?:[A-Za-z]+,?\s+)?[0-9]{4}-[0-9]{2}-[0-9]{2}")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<NaiveDate>())
+    }
+}
+
+// A phone number in any common layout, returned as its digits (keeping a
+// leading `+`), e.g. `+1 (555) 123-4567` -> `+15551234567`
+#[derive(Debug, Clone)]
+pub struct PhoneConverter;
+impl TypeConverter for PhoneConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let s = s.trim();
+        let digits: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
+        if digits.len() < 7 {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        let number = if s.starts_with('+') {
+            format!("+{}", digits)
+        } else {
+            digits
+        };
+        Ok(Box::new(number))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        // Starts with `+`, `(` or a digit and ends with a digit, so spaces
+        // around the number are not part of the match
+        Some(r"\+?[0-9(][0-9\s().-]{5,}[0-9]")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<String>())
+    }
+}
+
+// A numeric range such as `5..10` or `5-10`, returned as `Range<i64>`. A
+// leading `-` on either bound is a minus sign, so `-5--1` is `-5..-1`.
+#[derive(Debug, Clone)]
+pub struct RangeConverter;
+impl TypeConverter for RangeConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let (start, end) = match s.split_once("..") {
+            Some(bounds) => bounds,
+            None => {
+                // Skip the first character, which may be the start's sign
+                let skip = s
+                    .char_indices()
+                    .nth(1)
+                    .map(|(i, _)| i)
+                    .ok_or(ParseError::TypeConversionFailed)?;
+                let separator = s[skip..]
+                    .find('-')
+                    .map(|i| i + skip)
+                    .ok_or(ParseError::TypeConversionFailed)?;
+                (&s[..separator], &s[separator + 1..])
+            }
+        };
+        let bound = |n: &str| {
+            n.parse::<i64>()
+                .map_err(|_| ParseError::TypeConversionFailed)
+        };
+        let (start, end) = (bound(start)?, bound(end)?);
+        if start > end {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        Ok(Box::new(start..end))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?\d+(?:\.\.|-)-?\d+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<std::ops::Range<i64>>())
+    }
+}
+
+// A positive integer, returned as `NonZeroU64`
+#[derive(Debug, Clone)]
+pub struct NonZeroConverter;
+impl TypeConverter for NonZeroConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let n = s.parse::<u64>().map_err(|e| match e.kind() {
+            IntErrorKind::PosOverflow => ParseError::IntegerOverflow(s.to_string()),
+            _ => ParseError::TypeConversionFailed,
+        })?;
+        NonZeroU64::new(n)
+            .map(|n| Box::new(n) as Box<dyn std::any::Any + Send + Sync>)
+            .ok_or(ParseError::TypeConversionFailed)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"\d+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<NonZeroU64>())
+    }
+}
+
+// Parse an integer in `radix`, after an optional sign and `prefix` (e.g. `0x`)
+fn convert_radix(
+    s: &str,
+    prefix: &str,
+    radix: u32,
+) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let digits = match digits.get(..2) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => &digits[2..],
+        _ => digits,
+    };
+    let signed = if negative {
+        format!("-{}", digits)
+    } else {
+        digits.to_string()
+    };
+    i64::from_str_radix(&signed, radix)
+        .map(|n| Box::new(n) as Box<dyn std::any::Any + Send + Sync>)
+        .map_err(|e| match e.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                ParseError::IntegerOverflow(s.to_string())
+            }
+            _ => ParseError::TypeConversionFailed,
+        })
+}
+
+#[derive(Debug, Clone)]
+pub struct HexConverter;
+impl TypeConverter for HexConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        convert_radix(s, "0x", 16)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?(?:0[xX])?[0-9a-fA-F]+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OctConverter;
+impl TypeConverter for OctConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        convert_radix(s, "0o", 8)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?(?:0[oO])?[0-7]+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BinConverter;
+impl TypeConverter for BinConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        convert_radix(s, "0b", 2)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?(?:0[bB])?[01]+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct FloatConverter;
 impl TypeConverter for FloatConverter {
-    fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
         s.parse::<f64>()
-            .map(|n| Box::new(n) as Box<dyn std::any::Any>)
+            .map(|n| Box::new(n) as Box<dyn std::any::Any + Send + Sync>)
+            .map_err(|_| ParseError::TypeConversionFailed)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        // Optional sign, leading or trailing dot and exponent, or a whole-word
+        // `inf`/`infinity`/`nan`
+        Some(r"[-+]?(?:(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?|\b(?i:inf(?:inity)?|nan)\b)")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<f64>())
+    }
+}
+
+// Floats with optional US-style thousands grouping, e.g. 1,234.56
+#[derive(Debug, Clone)]
+pub struct GroupedFloatConverter;
+impl TypeConverter for GroupedFloatConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        s.replace(',', "")
+            .parse::<f64>()
+            .map(|n| Box::new(n) as Box<dyn std::any::Any + Send + Sync>)
             .map_err(|_| ParseError::TypeConversionFailed)
     }
 
     fn get_pattern(&self) -> Option<&str> {
-        Some(r"-?\d*\.?\d+")
+        Some(r"-?(?:\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d*\.?\d+)")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<f64>())
+    }
+}
+
+// Fixed-width hex read as a two's complement value, e.g. `{:x8s}` for an i32.
+// The result is sign-extended to i64.
+#[derive(Debug, Clone)]
+pub struct SignedHexConverter {
+    digits: usize,
+    pattern: String,
+}
+
+impl SignedHexConverter {
+    pub fn new(digits: usize) -> Self {
+        assert!(
+            (1..=16).contains(&digits),
+            "hex width must be 1 to 16 digits"
+        );
+        SignedHexConverter {
+            digits,
+            pattern: format!(r"(?:0[xX])?[0-9a-fA-F]{{{}}}", digits),
+        }
+    }
+}
+
+impl TypeConverter for SignedHexConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if digits.len() != self.digits {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        let unsigned =
+            u64::from_str_radix(digits, 16).map_err(|_| ParseError::TypeConversionFailed)?;
+
+        // Shift the sign bit up to bit 63, then arithmetic-shift back down
+        let unused_bits = 64 - self.digits as u32 * 4;
+        let value = ((unsigned << unused_bits) as i64) >> unused_bits;
+        Ok(Box::new(value))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(&self.pattern)
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<i64>())
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct WordConverter;
 impl TypeConverter for WordConverter {
-    fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
         Ok(Box::new(s.to_string()))
     }
 
     fn get_pattern(&self) -> Option<&str> {
         Some(r"\w+")
     }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<String>())
+    }
+}
+
+// Letters only (any script), returned as a `String`; unlike `w` it stops
+// at digits and underscores, so `{name:l}{n:d}` splits `Gate7`
+#[derive(Debug, Clone)]
+pub struct LetterConverter;
+impl TypeConverter for LetterConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        Ok(Box::new(s.to_string()))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"\p{L}+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<String>())
+    }
+}
+
+// A phrase that may contain spaces, returned as a `String`
+#[derive(Debug, Clone)]
+pub struct PhraseConverter;
+impl TypeConverter for PhraseConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        Ok(Box::new(s.to_string()))
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<String>())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct DateTimeConverter {
     format_type: String,
+    // Box input with an offset as `DateTime<FixedOffset>` rather than
+    // converting it to a `NaiveDateTime` in UTC
+    keep_offset: bool,
 }
+// Families tried, in order, by the `t` type
+const DATETIME_FAMILIES: [&str; 6] = ["tg", "ta", "te", "th", "ts", "ti"];
+
 impl TypeConverter for DateTimeConverter {
-    fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        if self.format_type == "t" {
+            return Self::convert_any(s);
+        }
+
         // Try various datetime formats
         let formats = match self.format_type.as_str() {
             // Generic date/time format (tg)
@@ -146,79 +1083,292 @@ impl TypeConverter for DateTimeConverter {
                 "%d/%b/%Y:%H:%M:%S %z", // 27/Dec/2024:19:57:55 +0000
             ],
 
-            // System log format (ts)
-            "ts" => vec![
-                "%b %d %Y %H:%M:%S", // Dec 27 2024 19:57:55
-            ],
+            // System log format (ts)
+            "ts" => vec![
+                "%b %d %Y %H:%M:%S", // Dec 27 2024 19:57:55
+            ],
+
+            // ISO format (ti), or ISO normalized to a UTC instant (tiu)
+            "ti" | "tiu" => vec![
+                "%Y-%m-%dT%H:%M:%S%.3f%:z", // 2024-12-27T19:57:55.000+00:00
+                "%Y-%m-%dT%H:%M:%S%:z",     // 2024-12-27T19:57:55+00:00
+                "%Y-%m-%dT%H:%M:%S%.3f",    // 2024-12-27T19:57:55.000
+                "%Y-%m-%dT%H:%M:%S",        // 2024-12-27T19:57:55
+                "%Y-%m-%d",                 // 2024-12-27
+            ],
+
+            _ => return Err(ParseError::TypeConversionFailed),
+        };
+
+        if self.format_type == "tiu" {
+            return Self::convert_utc(s, &formats);
+        }
+        Self::convert_naive(s, &formats, self.keep_offset)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        match self.format_type.as_str() {
+            "tg" => Some(
+                r"\d{1,2}/\d{1,2}/\d{4}(?:\s+\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?)?|\d{4}/\d{1,2}/\d{1,2}(?:\s+\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?)?|\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?",
+            ),
+            "ta" => Some(r"\d{1,2}/\d{1,2}/\d{4}(?:\s+\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?)?"),
+            "te" => Some(
+                r"(?:[A-Za-z]{3},\s+)?\d{1,2}\s+[A-Za-z]{3}\s+\d{4}(?:\s+\d{2}:\d{2}:\d{2}\s+[-+]\d{4})?",
+            ),
+            "th" => Some(r"\d{2}/[A-Za-z]{3}/\d{4}:\d{2}:\d{2}:\d{2}\s+[-+]\d{4}"),
+            "ts" => Some(r"[A-Za-z]{3}\s+\d{1,2}\s+\d{4}\s+\d{2}:\d{2}:\d{2}"),
+            "ti" | "tiu" => Some(
+                r"\d{4}-\d{1,2}-\d{1,2}(?:T\d{2}:\d{2}:\d{2}(?:\.\d{3})?(?:Z|[+-]\d{2}:\d{2})?)?",
+            ),
+            "t" => Some(ANY_DATETIME_PATTERN.as_str()),
+            _ => None,
+        }
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        // The other families box a date, time or datetime depending on the input
+        match self.format_type.as_str() {
+            "tiu" => Some(TypeId::of::<DateTime<Utc>>()),
+            "t" => Some(TypeId::of::<NaiveDateTime>()),
+            _ => None,
+        }
+    }
+}
+
+impl DateTimeConverter {
+    pub fn new(format_type: &str, keep_offset: bool) -> Self {
+        DateTimeConverter {
+            format_type: format_type.to_string(),
+            keep_offset,
+        }
+    }
+
+    // Try each family in `DATETIME_FAMILIES` order, keeping the first result
+    // that carries a date. Bare dates are taken as midnight.
+    fn convert_any(s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        for family in DATETIME_FAMILIES {
+            let converter = DateTimeConverter::new(family, false);
+            if let Ok(value) = converter.convert_shared(s) {
+                if let Some(dt) = value.downcast_ref::<NaiveDateTime>() {
+                    return Ok(Box::new(*dt));
+                }
+                if let Some(d) = value.downcast_ref::<NaiveDate>() {
+                    return Ok(Box::new(d.and_time(NaiveTime::MIN)));
+                }
+            }
+        }
+
+        Err(ParseError::TypeConversionFailed)
+    }
+
+    // Try each format in turn as a datetime, then a bare date, then a bare time
+    fn convert_naive(
+        s: &str,
+        formats: &[&str],
+        keep_offset: bool,
+    ) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        // Try to parse using any of the supported formats
+        for format in formats {
+            match format {
+                f if f.contains("%z") || f.contains("%:z") => {
+                    if let Ok(dt) = DateTime::parse_from_str(s, format) {
+                        if keep_offset {
+                            return Ok(Box::new(dt));
+                        }
+                        return Ok(Box::new(dt.naive_utc()));
+                    }
+                }
+                _ => {
+                    if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+                        return Ok(Box::new(dt));
+                    }
+                }
+            }
+        }
+
+        // Try parsing as NaiveDate for date-only formats
+        for format in formats {
+            if let Ok(d) = NaiveDate::parse_from_str(s, format) {
+                return Ok(Box::new(d));
+            }
+        }
+
+        // Try parsing as NaiveTime for time-only formats
+        for format in formats {
+            if let Ok(t) = NaiveTime::parse_from_str(s, format) {
+                return Ok(Box::new(t));
+            }
+        }
+
+        Err(ParseError::TypeConversionFailed)
+    }
+
+    // Parse into a `DateTime<Utc>`, converting any offset to UTC and treating
+    // offset-less input (including bare dates, at midnight) as already UTC.
+    fn convert_utc(
+        s: &str,
+        formats: &[&str],
+    ) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        for format in formats {
+            if format.contains("%z") || format.contains("%:z") {
+                if let Ok(dt) = DateTime::parse_from_str(s, format) {
+                    return Ok(Box::new(dt.with_timezone(&Utc)));
+                }
+            } else if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+                return Ok(Box::new(dt.and_utc()));
+            }
+        }
+
+        for format in formats {
+            if let Ok(d) = NaiveDate::parse_from_str(s, format) {
+                return Ok(Box::new(d.and_time(NaiveTime::MIN).and_utc()));
+            }
+        }
+
+        Err(ParseError::TypeConversionFailed)
+    }
+}
+
+// A datetime family extended with user-registered strftime formats, which
+// are tried after the family's own
+#[derive(Debug, Clone)]
+struct ExtendedDateTimeConverter {
+    base: DateTimeConverter,
+    extra_formats: Vec<String>,
+    pattern: String,
+}
+
+impl ExtendedDateTimeConverter {
+    fn new(format_type: &str, extra_formats: Vec<String>) -> Result<Self, ParseError> {
+        if !matches!(format_type, "tg" | "ta" | "te" | "th" | "ts" | "ti" | "tiu") {
+            return Err(ParseError::InvalidFormat);
+        }
+        let base = DateTimeConverter::new(format_type, false);
+        let mut alternatives = vec![base.get_pattern().unwrap_or_default().to_string()];
+        for format in &extra_formats {
+            alternatives.push(strftime_pattern(format)?);
+        }
+        Ok(ExtendedDateTimeConverter {
+            base,
+            extra_formats,
+            pattern: alternatives.join("|"),
+        })
+    }
+}
+
+impl TypeConverter for ExtendedDateTimeConverter {
+    convert_via_shared!();
 
-            // ISO format (ti)
-            "ti" => vec![
-                "%Y-%m-%dT%H:%M:%S%.3f%:z", // 2024-12-27T19:57:55.000+00:00
-                "%Y-%m-%dT%H:%M:%S%:z",     // 2024-12-27T19:57:55+00:00
-                "%Y-%m-%dT%H:%M:%S%.3f",    // 2024-12-27T19:57:55.000
-                "%Y-%m-%dT%H:%M:%S",        // 2024-12-27T19:57:55
-                "%Y-%m-%d",                 // 2024-12-27
-            ],
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        self.base.convert_shared(s).or_else(|_| {
+            let formats: Vec<&str> = self.extra_formats.iter().map(String::as_str).collect();
+            if self.base.format_type == "tiu" {
+                DateTimeConverter::convert_utc(s, &formats)
+            } else {
+                DateTimeConverter::convert_naive(s, &formats, self.base.keep_offset)
+            }
+        })
+    }
 
-            _ => return Err(ParseError::TypeConversionFailed),
-        };
+    fn get_pattern(&self) -> Option<&str> {
+        Some(&self.pattern)
+    }
 
-        // Try to parse using any of the supported formats
-        for format in &formats {
-            match format {
-                f if f.contains("%z") || f.contains("%:z") => {
-                    if let Ok(dt) = DateTime::parse_from_str(s, format) {
-                        return Ok(Box::new(dt.naive_utc()));
-                    }
+    fn output_type_id(&self) -> Option<TypeId> {
+        self.base.output_type_id()
+    }
+}
+
+// A regex matching the text a strftime format produces
+fn strftime_pattern(format: &str) -> Result<String, ParseError> {
+    let mut pattern = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            if c.is_whitespace() {
+                pattern.push_str(r"\s+");
+            } else {
+                pattern.push_str(&regex::escape(&c.to_string()));
+            }
+            continue;
+        }
+        let spec = match chars.next() {
+            Some('.') => {
+                // `%.3f` and friends: fractional seconds
+                while chars.clone().next().is_some_and(|c| c.is_ascii_digit()) {
+                    chars.next();
                 }
-                _ => {
-                    if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
-                        return Ok(Box::new(dt));
-                    }
+                if chars.next() != Some('f') {
+                    return Err(ParseError::InvalidFormat);
                 }
+                r"\.\d+"
             }
-        }
-
-        // Try parsing as NaiveDate for date-only formats
-        for format in &formats {
-            if let Ok(d) = NaiveDate::parse_from_str(s, format) {
-                return Ok(Box::new(d));
+            Some(':') => {
+                if chars.next() != Some('z') {
+                    return Err(ParseError::InvalidFormat);
+                }
+                r"(?:Z|[-+]\d{2}:\d{2})"
             }
-        }
+            Some('Y') => r"\d{4}",
+            Some('y') => r"\d{2}",
+            Some('m' | 'd' | 'H' | 'I' | 'M' | 'S') => r"\d{1,2}",
+            Some('e') => r"\s?\d{1,2}",
+            Some('j') => r"\d{3}",
+            Some('f') => r"\d+",
+            Some('b' | 'h' | 'a') => r"[A-Za-z]{3}",
+            Some('B' | 'A') => r"[A-Za-z]+",
+            Some('p') => r"(?:AM|PM)",
+            Some('z') => r"[-+]\d{4}",
+            Some('%') => "%",
+            _ => return Err(ParseError::InvalidFormat),
+        };
+        pattern.push_str(spec);
+    }
+    Ok(pattern)
+}
 
-        // Try parsing as NaiveTime for time-only formats
-        for format in &formats {
-            if let Ok(t) = NaiveTime::parse_from_str(s, format) {
-                return Ok(Box::new(t));
+// A `tg` or `ti` datetime as a `std::time::SystemTime`. Input without an
+// offset is taken as UTC.
+#[derive(Debug, Clone)]
+pub struct SystemTimeConverter;
+impl TypeConverter for SystemTimeConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let iso = DateTimeConverter::new("tiu", false);
+        if let Ok(value) = iso.convert_shared(s) {
+            if let Some(dt) = value.downcast_ref::<DateTime<Utc>>() {
+                return Ok(Box::new(SystemTime::from(*dt)));
             }
         }
 
-        Err(ParseError::TypeConversionFailed)
+        let generic = DateTimeConverter::new("tg", false);
+        let value = generic.convert_shared(s)?;
+        let naive = if let Some(dt) = value.downcast_ref::<NaiveDateTime>() {
+            *dt
+        } else if let Some(d) = value.downcast_ref::<NaiveDate>() {
+            d.and_time(NaiveTime::MIN)
+        } else {
+            return Err(ParseError::TypeConversionFailed);
+        };
+        Ok(Box::new(SystemTime::from(naive.and_utc())))
     }
 
     fn get_pattern(&self) -> Option<&str> {
-        match self.format_type.as_str() {
-            "tg" => Some(
-                r"\d{1,2}/\d{1,2}/\d{4}(?:\s+\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?)?|\d{4}/\d{1,2}/\d{1,2}(?:\s+\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?)?|\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?",
-            ),
-            "ta" => Some(r"\d{1,2}/\d{1,2}/\d{4}(?:\s+\d{1,2}:\d{2}(?::\d{2})?(?:\s*(?:AM|PM))?)?"),
-            "te" => Some(
-                r"(?:[A-Za-z]{3},\s+)?\d{1,2}\s+[A-Za-z]{3}\s+\d{4}(?:\s+\d{2}:\d{2}:\d{2}\s+[-+]\d{4})?",
-            ),
-            "th" => Some(r"\d{2}/[A-Za-z]{3}/\d{4}:\d{2}:\d{2}:\d{2}\s+[-+]\d{4}"),
-            "ts" => Some(r"[A-Za-z]{3}\s+\d{1,2}\s+\d{4}\s+\d{2}:\d{2}:\d{2}"),
-            "ti" => Some(
-                r"\d{4}-\d{1,2}-\d{1,2}(?:T\d{2}:\d{2}:\d{2}(?:\.\d{3})?(?:Z|[+-]\d{2}:\d{2})?)?",
-            ),
-            _ => None,
-        }
+        Some(SYSTIME_PATTERN.as_str())
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<SystemTime>())
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct DateConverter;
 impl TypeConverter for DateConverter {
-    fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
         // Try various date formats
         let formats = [
             // Standard date formats
@@ -254,12 +1404,170 @@ impl TypeConverter for DateConverter {
             r"(?:(?:19|20)\d\d[-/](?:0[1-9]|1[0-2])[-/](?:0[1-9]|[12]\d|3[01])|(?:0[1-9]|[12]\d|3[01])[-/](?:0[1-9]|1[0-2])[-/](?:19|20)\d\d|(?:0[1-9]|[12]\d|3[01])(?:\s+|-)?(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|Jun(?:e)?|Jul(?:y)?|Aug(?:ust)?|Sep(?:tember)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)(?:\s*,\s*|\s+|-)?(?:19|20)\d\d|(?:19|20)\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01]))",
         )
     }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<NaiveDate>())
+    }
+}
+
+#[cfg(feature = "locale")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    French,
+    German,
+}
+
+#[cfg(feature = "locale")]
+impl Locale {
+    // Localized month names and abbreviations, lowercase, to English abbreviations
+    fn months(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::French => &[
+                ("janvier", "Jan"),
+                ("janv", "Jan"),
+                ("février", "Feb"),
+                ("févr", "Feb"),
+                ("fév", "Feb"),
+                ("mars", "Mar"),
+                ("avril", "Apr"),
+                ("avr", "Apr"),
+                ("mai", "May"),
+                ("juin", "Jun"),
+                ("juillet", "Jul"),
+                ("juil", "Jul"),
+                ("août", "Aug"),
+                ("septembre", "Sep"),
+                ("sept", "Sep"),
+                ("octobre", "Oct"),
+                ("oct", "Oct"),
+                ("novembre", "Nov"),
+                ("nov", "Nov"),
+                ("décembre", "Dec"),
+                ("déc", "Dec"),
+            ],
+            Locale::German => &[
+                ("januar", "Jan"),
+                ("jänner", "Jan"),
+                ("jan", "Jan"),
+                ("februar", "Feb"),
+                ("feb", "Feb"),
+                ("märz", "Mar"),
+                ("mär", "Mar"),
+                ("april", "Apr"),
+                ("apr", "Apr"),
+                ("mai", "May"),
+                ("juni", "Jun"),
+                ("jun", "Jun"),
+                ("juli", "Jul"),
+                ("jul", "Jul"),
+                ("august", "Aug"),
+                ("aug", "Aug"),
+                ("september", "Sep"),
+                ("sept", "Sep"),
+                ("sep", "Sep"),
+                ("oktober", "Oct"),
+                ("okt", "Oct"),
+                ("november", "Nov"),
+                ("nov", "Nov"),
+                ("dezember", "Dec"),
+                ("dez", "Dec"),
+            ],
+        }
+    }
+}
+
+// `DateConverter` that also accepts month names in another language, e.g.
+// `27 déc. 2024` or `27. Dezember 2024`
+#[cfg(feature = "locale")]
+#[derive(Debug, Clone)]
+pub struct LocaleDateConverter {
+    locale: Locale,
+    pattern: String,
+}
+
+#[cfg(feature = "locale")]
+impl LocaleDateConverter {
+    pub fn new(locale: Locale) -> Self {
+        LocaleDateConverter {
+            locale,
+            pattern: format!(
+                r"\d{{1,2}}\.?\s+[^\W\d_]+\.?,?\s+(?:19|20)\d\d|{}",
+                DateConverter.get_pattern().unwrap()
+            ),
+        }
+    }
+
+    // Rewrite localized month tokens to English so `DateConverter` can parse them
+    fn normalize(&self, s: &str) -> String {
+        s.split_whitespace()
+            .map(|token| {
+                let bare = token.trim_end_matches(['.', ',']).to_lowercase();
+                if bare.chars().all(|c| c.is_ascii_digit()) {
+                    return bare;
+                }
+                self.locale
+                    .months()
+                    .iter()
+                    .find(|(name, _)| *name == bare)
+                    .map_or_else(|| token.to_string(), |(_, english)| english.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(feature = "locale")]
+impl TypeConverter for LocaleDateConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        DateConverter
+            .convert_shared(s)
+            .or_else(|_| DateConverter.convert_shared(&self.normalize(s)))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(&self.pattern)
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<NaiveDate>())
+    }
+}
+
+// Standard (RFC 4648) base64, decoded into a `Vec<u8>`
+#[cfg(feature = "base64")]
+#[derive(Debug, Clone)]
+pub struct Base64Converter;
+
+#[cfg(feature = "base64")]
+impl TypeConverter for Base64Converter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        use base64::Engine;
+
+        base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map(|bytes| Box::new(bytes) as Box<dyn std::any::Any + Send + Sync>)
+            .map_err(|_| ParseError::TypeConversionFailed)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"[A-Za-z0-9+/]+=*")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<Vec<u8>>())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TimeConverter;
 impl TypeConverter for TimeConverter {
-    fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
         // Try various time formats
         let formats = [
             // Standard time formats
@@ -283,39 +1591,456 @@ impl TypeConverter for TimeConverter {
     fn get_pattern(&self) -> Option<&str> {
         Some(r"(?:[01]\d|2[0-3]):[0-5]\d(?::[0-5]\d)?(?:\s*[AaPp][Mm])?(?:\s*[-+]\d{2}:?\d{2})?")
     }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<NaiveTime>())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    pub value: f64,
+    pub unit: char,
+}
+
+impl Temperature {
+    pub fn to_celsius(&self) -> f64 {
+        match self.unit {
+            'F' => (self.value - 32.0) * 5.0 / 9.0,
+            'K' => self.value - 273.15,
+            _ => self.value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TemperatureConverter;
+impl TypeConverter for TemperatureConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let unit = s
+            .chars()
+            .last()
+            .map(|c| c.to_ascii_uppercase())
+            .filter(|c| matches!(c, 'C' | 'F' | 'K'))
+            .ok_or(ParseError::TypeConversionFailed)?;
+        let number = s[..s.len() - 1].trim_end().trim_end_matches('°').trim_end();
+        let value = number
+            .parse::<f64>()
+            .map_err(|_| ParseError::TypeConversionFailed)?;
+        Ok(Box::new(Temperature { value, unit }))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?\d+(?:\.\d+)?\s*°?[CFK]")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<Temperature>())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogLevelConverter;
+impl TypeConverter for LogLevelConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let level = match s.to_ascii_lowercase().as_str() {
+            "trace" => LogLevel::Trace,
+            "debug" => LogLevel::Debug,
+            "info" => LogLevel::Info,
+            "warn" | "warning" => LogLevel::Warn,
+            "err" | "error" => LogLevel::Error,
+            _ => return Err(ParseError::TypeConversionFailed),
+        };
+        Ok(Box::new(level))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        // Level tokens match in any case, even in a case-sensitive parser
+        Some(r"(?i:trace|debug|info|warn(?:ing)?|err(?:or)?)")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<LogLevel>())
+    }
+}
+
+type ChainTransform = dyn Fn(Box<dyn Any + Send + Sync>) -> Result<Box<dyn Any + Send + Sync>, ParseError>
+    + Send
+    + Sync;
+
+// Runs an inner converter, then passes its boxed value through a transform,
+// e.g. `d` followed by a mapping to a domain type
+#[derive(Clone)]
+pub struct ChainConverter {
+    inner: Box<dyn TypeConverter>,
+    transform: Arc<ChainTransform>,
+}
+
+impl ChainConverter {
+    pub fn new<F>(inner: Box<dyn TypeConverter>, transform: F) -> Self
+    where
+        F: Fn(Box<dyn Any + Send + Sync>) -> Result<Box<dyn Any + Send + Sync>, ParseError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        ChainConverter {
+            inner,
+            transform: Arc::new(transform),
+        }
+    }
+}
+
+impl std::fmt::Debug for ChainConverter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainConverter")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TypeConverter for ChainConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        (self.transform)(self.inner.convert_shared(s)?)
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        self.inner.get_pattern()
+    }
+
+    // `output_type_id` is left at `None`: the transform may change the type
+    fn is_whitespace_sensitive(&self) -> bool {
+        self.inner.is_whitespace_sensitive()
+    }
+}
+
+// A "lat, lon" pair in decimal degrees, returned as `(f64, f64)`
+#[derive(Debug, Clone)]
+pub struct LatLonConverter;
+impl TypeConverter for LatLonConverter {
+    convert_via_shared!();
+
+    fn convert_shared(&self, s: &str) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+        let (lat, lon) = s.split_once(',').ok_or(ParseError::TypeConversionFailed)?;
+        let lat = lat
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ParseError::TypeConversionFailed)?;
+        let lon = lon
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ParseError::TypeConversionFailed)?;
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(ParseError::TypeConversionFailed);
+        }
+        Ok(Box::new((lat, lon)))
+    }
+
+    fn get_pattern(&self) -> Option<&str> {
+        Some(r"-?\d+\.\d+,\s*-?\d+\.\d+")
+    }
+
+    fn output_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<(f64, f64)>())
+    }
 }
 
 lazy_static::lazy_static! {
+    static ref ANY_DATETIME_PATTERN: String = DATETIME_FAMILIES
+        .iter()
+        .filter_map(|family| {
+            let converter = DateTimeConverter::new(family, false);
+            converter.get_pattern().map(|p| format!("(?:{})", p))
+        })
+        .collect::<Vec<_>>()
+        .join("|");
+    static ref SYSTIME_PATTERN: String = ["ti", "tg"]
+        .iter()
+        .filter_map(|family| {
+            let converter = DateTimeConverter::new(family, false);
+            converter.get_pattern().map(|p| format!("(?:{})", p))
+        })
+        .collect::<Vec<_>>()
+        .join("|");
     static ref DEFAULT_TYPES: HashMap<String, Box<dyn TypeConverter>> = {
         let mut m = HashMap::new();
         m.insert("d".to_string(), Box::new(IntConverter) as Box<dyn TypeConverter>);
+        m.insert("x".to_string(), Box::new(HexConverter) as Box<dyn TypeConverter>);
+        m.insert("o".to_string(), Box::new(OctConverter) as Box<dyn TypeConverter>);
+        m.insert("b".to_string(), Box::new(BinConverter) as Box<dyn TypeConverter>);
+        m.insert("nz".to_string(), Box::new(NonZeroConverter) as Box<dyn TypeConverter>);
+        m.insert("ord".to_string(), Box::new(OrdinalConverter) as Box<dyn TypeConverter>);
+        m.insert("dw".to_string(), Box::new(WeekdayDateConverter) as Box<dyn TypeConverter>);
+        m.insert("phone".to_string(), Box::new(PhoneConverter) as Box<dyn TypeConverter>);
+        m.insert("range".to_string(), Box::new(RangeConverter) as Box<dyn TypeConverter>);
         m.insert("f".to_string(), Box::new(FloatConverter) as Box<dyn TypeConverter>);
+        m.insert("fn".to_string(), Box::new(GroupedFloatConverter) as Box<dyn TypeConverter>);
+        m.insert("hexbytes".to_string(), Box::new(HexBytesConverter) as Box<dyn TypeConverter>);
         m.insert("w".to_string(), Box::new(WordConverter) as Box<dyn TypeConverter>);
-        m.insert("tg".to_string(), Box::new(DateTimeConverter { format_type: "tg".to_string() }) as Box<dyn TypeConverter>);
-        m.insert("ta".to_string(), Box::new(DateTimeConverter { format_type: "ta".to_string() }) as Box<dyn TypeConverter>);
-        m.insert("te".to_string(), Box::new(DateTimeConverter { format_type: "te".to_string() }) as Box<dyn TypeConverter>);
-        m.insert("th".to_string(), Box::new(DateTimeConverter { format_type: "th".to_string() }) as Box<dyn TypeConverter>);
-        m.insert("ts".to_string(), Box::new(DateTimeConverter { format_type: "ts".to_string() }) as Box<dyn TypeConverter>);
-        m.insert("ti".to_string(), Box::new(DateTimeConverter { format_type: "ti".to_string() }) as Box<dyn TypeConverter>);
+        m.insert("l".to_string(), Box::new(LetterConverter) as Box<dyn TypeConverter>);
+        m.insert("S".to_string(), Box::new(PhraseConverter) as Box<dyn TypeConverter>);
+        m.insert("x2s".to_string(), Box::new(SignedHexConverter::new(2)) as Box<dyn TypeConverter>);
+        m.insert("x4s".to_string(), Box::new(SignedHexConverter::new(4)) as Box<dyn TypeConverter>);
+        m.insert("x8s".to_string(), Box::new(SignedHexConverter::new(8)) as Box<dyn TypeConverter>);
+        m.insert("x16s".to_string(), Box::new(SignedHexConverter::new(16)) as Box<dyn TypeConverter>);
+        m.insert("t".to_string(), Box::new(DateTimeConverter::new("t", false)) as Box<dyn TypeConverter>);
+        m.insert("tg".to_string(), Box::new(DateTimeConverter::new("tg", false)) as Box<dyn TypeConverter>);
+        m.insert("ta".to_string(), Box::new(DateTimeConverter::new("ta", false)) as Box<dyn TypeConverter>);
+        m.insert("te".to_string(), Box::new(DateTimeConverter::new("te", false)) as Box<dyn TypeConverter>);
+        m.insert("th".to_string(), Box::new(DateTimeConverter::new("th", false)) as Box<dyn TypeConverter>);
+        m.insert("ts".to_string(), Box::new(DateTimeConverter::new("ts", false)) as Box<dyn TypeConverter>);
+        m.insert("ti".to_string(), Box::new(DateTimeConverter::new("ti", false)) as Box<dyn TypeConverter>);
+        m.insert("tiu".to_string(), Box::new(DateTimeConverter::new("tiu", false)) as Box<dyn TypeConverter>);
+        m.insert("temp".to_string(), Box::new(TemperatureConverter) as Box<dyn TypeConverter>);
+        m.insert("systime".to_string(), Box::new(SystemTimeConverter) as Box<dyn TypeConverter>);
+        m.insert("level".to_string(), Box::new(LogLevelConverter) as Box<dyn TypeConverter>);
+        #[cfg(feature = "base64")]
+        m.insert("b64".to_string(), Box::new(Base64Converter) as Box<dyn TypeConverter>);
+        m.insert("latlon".to_string(), Box::new(LatLonConverter) as Box<dyn TypeConverter>);
         m
     };
 }
 
+// The default converters merged with custom ones, built once and shared by
+// every parser created with `Parser::new_with_registry`
+#[derive(Debug, Clone)]
+pub struct ConverterRegistry {
+    converters: Arc<HashMap<String, Box<dyn TypeConverter>>>,
+}
+
+impl ConverterRegistry {
+    // Extra types are layered over the defaults and may replace them
+    pub fn new(extra_types: HashMap<String, Box<dyn TypeConverter>>) -> Self {
+        let mut converters = Parser::get_default_type_converters();
+        converters.extend(extra_types);
+        ConverterRegistry {
+            converters: Arc::new(converters),
+        }
+    }
+}
+
+// Configures a `Parser` beyond what `Parser::new` exposes
+#[derive(Debug)]
+pub struct ParserBuilder {
+    format: String,
+    case_sensitive: bool,
+    extra_types: HashMap<String, Box<dyn TypeConverter>>,
+    strict: bool,
+    cache_capacity: Option<usize>,
+    isolated_numbers: bool,
+    normalize_whitespace: bool,
+    scientific_integers: bool,
+    absent_as_empty: bool,
+    // Extra strftime formats per datetime type key
+    datetime_formats: HashMap<String, Vec<String>>,
+}
+
+impl ParserBuilder {
+    pub fn new(format: &str) -> Self {
+        ParserBuilder {
+            format: format.to_string(),
+            case_sensitive: false,
+            extra_types: HashMap::new(),
+            strict: false,
+            cache_capacity: None,
+            isolated_numbers: false,
+            normalize_whitespace: false,
+            scientific_integers: false,
+            absent_as_empty: false,
+            datetime_formats: HashMap::new(),
+        }
+    }
+
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    pub fn types(mut self, extra_types: HashMap<String, Box<dyn TypeConverter>>) -> Self {
+        self.extra_types.extend(extra_types);
+        self
+    }
+
+    // Reject formats ending in an untyped field, whose extent is easy to get
+    // wrong (e.g. whether trailing whitespace is captured)
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    // Remember up to `capacity` converted values so repeated tokens (status
+    // words, hostnames...) are converted once
+    pub fn conversion_cache(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    // When searching, skip numeric matches that are part of a dotted number
+    // such as a version string (`1`, `2` and `3` in `v1.2.3`)
+    pub fn isolated_numbers(mut self, isolated_numbers: bool) -> Self {
+        self.isolated_numbers = isolated_numbers;
+        self
+    }
+
+    // Collapse runs of whitespace to a single space in both the format and
+    // the input, so inconsistently spaced text still matches. Spans then
+    // refer to the collapsed input.
+    pub fn normalize_whitespace(mut self, normalize_whitespace: bool) -> Self {
+        self.normalize_whitespace = normalize_whitespace;
+        self
+    }
+
+    // Report fields that did not take part in the match (e.g. from another
+    // `{|}` branch) as empty strings instead of leaving them out
+    pub fn absent_as_empty(mut self, absent_as_empty: bool) -> Self {
+        self.absent_as_empty = absent_as_empty;
+        self
+    }
+
+    // Let `d` fields accept integer-valued scientific notation (`1e3`)
+    pub fn scientific_integers(mut self, scientific_integers: bool) -> Self {
+        self.scientific_integers = scientific_integers;
+        self
+    }
+
+    // Also accept `strftime_format` for the datetime type `type_key` (e.g.
+    // `"tg"`), after that type's built-in formats
+    pub fn datetime_format(mut self, type_key: &str, strftime_format: &str) -> Self {
+        self.datetime_formats
+            .entry(type_key.to_string())
+            .or_default()
+            .push(strftime_format.to_string());
+        self
+    }
+
+    pub fn build(mut self) -> Result<Parser, ParseError> {
+        if self.scientific_integers {
+            self.extra_types
+                .insert("d".to_string(), Box::new(ScientificIntConverter));
+        }
+        for (type_key, formats) in self.datetime_formats.drain() {
+            let converter = ExtendedDateTimeConverter::new(&type_key, formats)?;
+            self.extra_types.insert(type_key, Box::new(converter));
+        }
+        let format = if self.normalize_whitespace {
+            collapse_whitespace(&self.format)
+        } else {
+            self.format
+        };
+        let mut parser = Parser::new_with_types(&format, self.case_sensitive, self.extra_types)?;
+        if self.strict && parser.trailing_untyped {
+            return Err(ParseError::AmbiguousTrailingField);
+        }
+        parser.conversion_cache = self
+            .cache_capacity
+            .map(|capacity| Mutex::new(ConversionCache::new(capacity)));
+        parser.isolated_numbers = self.isolated_numbers;
+        parser.normalize_whitespace = self.normalize_whitespace;
+        parser.absent_as_empty = self.absent_as_empty;
+        Ok(parser)
+    }
+}
+
+// The field that stopped `capture_fields`
+struct FieldFailure {
+    index: usize,
+    name: String,
+    value: String,
+    error: ParseError,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchDiagnosis {
+    // The input does not have the shape of the format
+    NoMatch,
+    // The input follows the format up to and including its first
+    // `matched_fields` fields, then diverges
+    Diverged {
+        matched_fields: usize,
+    },
+    // The input matched, but a field's text could not be converted
+    ConversionFailed {
+        index: usize,
+        name: String,
+        value: String,
+        reason: String,
+    },
+    Matched,
+}
+
 type FormatParseResult = (
-    String,                  // exact pattern
-    String,                  // search pattern
-    HashMap<String, usize>,  // field map
-    HashMap<String, String>, // field types
+    String,                        // exact pattern
+    String,                        // search pattern
+    HashMap<String, usize>,        // field map
+    HashMap<String, String>,       // field types
+    HashMap<String, String>,       // repeated field patterns
+    HashMap<String, String>,       // default values
+    Vec<String>,                   // pattern up to the end of each field
+    HashMap<String, CaseModifier>, // case modifiers
+    HashMap<String, String>,       // fields that must repeat an earlier field
+    Vec<(usize, String)>,          // groups repeating an earlier field's name
+    HashMap<String, String>,       // sub-formats of record fields
+    bool,                          // whether a branch ends in an untyped field
 );
 
+// A `:lower` or `:upper` modifier after a field's type
+#[derive(Debug, Clone, Copy)]
+enum CaseModifier {
+    Lower,
+    Upper,
+}
+
 impl Parser {
+    pub fn builder(format: &str) -> ParserBuilder {
+        ParserBuilder::new(format)
+    }
+
     pub fn new_with_types(
         format: &str,
         case_sensitive: bool,
         extra_types: HashMap<String, Box<dyn TypeConverter>>,
     ) -> Result<Self, ParseError> {
-        let (exact_pattern, search_pattern, field_map, field_types) =
-            Self::parse_format(format, &extra_types)?;
+        let registry = ConverterRegistry::new(extra_types);
+        Self::new_with_registry(format, case_sensitive, &registry)
+    }
+
+    // Use the converters of a registry built once for many formats
+    pub fn new_with_registry(
+        format: &str,
+        case_sensitive: bool,
+        registry: &ConverterRegistry,
+    ) -> Result<Self, ParseError> {
+        let type_converters = Arc::clone(&registry.converters);
+
+        let (
+            exact_pattern,
+            search_pattern,
+            field_map,
+            field_types,
+            repeated_patterns,
+            defaults,
+            field_prefixes,
+            case_modifiers,
+            references,
+            backreferences,
+            sub_formats,
+            trailing_untyped,
+        ) = Self::parse_format(format, &type_converters)?;
         let flags = if case_sensitive {
             RegexBuilder::new(&format!("^{}\\s*$", exact_pattern))
         } else {
@@ -334,105 +2059,382 @@ impl Parser {
         };
         let search_re = flags.build().map_err(|_| ParseError::InvalidFormat)?;
 
+        let mut repeated = HashMap::new();
+        for (name, repeated_pattern) in repeated_patterns {
+            let repeated_re = RegexBuilder::new(&repeated_pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map_err(|_| ParseError::InvalidFormat)?;
+            repeated.insert(name, repeated_re);
+        }
+
+        let mut sub_parsers = HashMap::new();
+        for (name, sub_format) in sub_formats {
+            let sub_parser = Self::new_with_registry(&sub_format, case_sensitive, registry)?;
+            sub_parsers.insert(name, sub_parser);
+        }
+
+        let literal = if field_map.is_empty() {
+            Self::plain_literal(format, case_sensitive)
+        } else {
+            None
+        };
+
         Ok(Parser {
             exact_re,
             search_re,
             field_map,
             field_types,
-            type_converters: extra_types,
+            repeated,
+            defaults,
+            field_prefixes,
+            case_modifiers,
+            references,
+            backreferences,
+            sub_parsers,
+            trailing_untyped,
+            type_converters,
+            case_sensitive,
+            literal,
+            conversion_cache: None,
+            isolated_numbers: false,
+            normalize_whitespace: false,
+            absent_as_empty: false,
+        })
+    }
+
+    // The text a field-less format matches, unless it uses flexible
+    // punctuation or wildcards that need the regex
+    fn plain_literal(format: &str, case_sensitive: bool) -> Option<String> {
+        if format.contains([',', '=', '+', '-', '*', '|'])
+            || (!case_sensitive && !format.is_ascii())
+        {
+            return None;
+        }
+        Some(format.replace("{{", "{").replace("}}", "}"))
+    }
+
+    fn parse_literal(&self, literal: &str, text: &str) -> Option<ParseResult> {
+        let head = text.get(..literal.len())?;
+        let matches = if self.case_sensitive {
+            head == literal
+        } else {
+            head.eq_ignore_ascii_case(literal)
+        };
+        // The anchored pattern allows trailing whitespace
+        if !matches || !text[literal.len()..].trim_start().is_empty() {
+            return None;
+        }
+        Some(ParseResult {
+            converted: Vec::new(),
+            raw: Vec::new(),
+            field_map: HashMap::new(),
+            span: (0, text.len()),
+            field_spans: Vec::new(),
+            type_keys: Vec::new(),
         })
     }
 
-    pub fn new(format: &str, case_sensitive: bool) -> Result<Self, ParseError> {
-        let type_converters = Self::get_default_type_converters();
-        Self::new_with_types(format, case_sensitive, type_converters)
+    pub fn new(format: &str, case_sensitive: bool) -> Result<Self, ParseError> {
+        Self::new_with_types(format, case_sensitive, HashMap::new())
+    }
+
+    fn get_default_type_converters() -> HashMap<String, Box<dyn TypeConverter>> {
+        DEFAULT_TYPES
+            .iter()
+            .map(|(k, converter)| (k.clone(), converter.clone_box()))
+            .collect()
+    }
+
+    fn convert_value(
+        &self,
+        type_name: &str,
+        converter: &dyn TypeConverter,
+        value: &str,
+    ) -> Result<Value, ParseError> {
+        let Some(cache) = &self.conversion_cache else {
+            return Self::convert_to_value(converter, value);
+        };
+
+        let key = (type_name.to_string(), value.to_string());
+        if let Some(hit) = cache.lock().unwrap().get(&key) {
+            return Ok(Value::Shared(hit));
+        }
+        // Only shareable values can be cached
+        let converted = Self::convert_to_value(converter, value)?;
+        if let Value::Shared(shared) = &converted {
+            cache.lock().unwrap().insert(key, shared.clone());
+        }
+        Ok(converted)
     }
 
-    fn get_default_type_converters() -> HashMap<String, Box<dyn TypeConverter>> {
-        let mut default_types = HashMap::new();
-        for k in DEFAULT_TYPES.keys() {
-            if let Some(converter) = match k.as_str() {
-                "d" => Some(Box::new(IntConverter) as Box<dyn TypeConverter>),
-                "f" => Some(Box::new(FloatConverter) as Box<dyn TypeConverter>),
-                "w" => Some(Box::new(WordConverter) as Box<dyn TypeConverter>),
-                "tg" => Some(Box::new(DateTimeConverter {
-                    format_type: "tg".to_string(),
-                }) as Box<dyn TypeConverter>),
-                "ta" => Some(Box::new(DateTimeConverter {
-                    format_type: "ta".to_string(),
-                }) as Box<dyn TypeConverter>),
-                "te" => Some(Box::new(DateTimeConverter {
-                    format_type: "te".to_string(),
-                }) as Box<dyn TypeConverter>),
-                "th" => Some(Box::new(DateTimeConverter {
-                    format_type: "th".to_string(),
-                }) as Box<dyn TypeConverter>),
-                "ts" => Some(Box::new(DateTimeConverter {
-                    format_type: "ts".to_string(),
-                }) as Box<dyn TypeConverter>),
-                "ti" => Some(Box::new(DateTimeConverter {
-                    format_type: "ti".to_string(),
-                }) as Box<dyn TypeConverter>),
-                _ => None,
-            } {
-                default_types.insert(k.clone(), converter);
-            }
-        }
-        default_types
+    // Shared if the converter implements `convert_shared`, local otherwise
+    fn convert_to_value(converter: &dyn TypeConverter, value: &str) -> Result<Value, ParseError> {
+        match converter.convert_shared(value) {
+            Ok(converted) => Ok(Value::Shared(Arc::from(converted))),
+            Err(ParseError::NotShareable) => converter
+                .convert(value)
+                .map(|converted| Value::Local(Rc::from(converted))),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Each repetition's text, checked against the field's type if it has one
+    fn collect_repeated(
+        &self,
+        field_name: &str,
+        repeated_re: &Regex,
+        value: &str,
+    ) -> Result<Vec<String>, ParseError> {
+        let converter = self
+            .field_types
+            .get(field_name)
+            .and_then(|type_name| Some((type_name, self.type_converters.get(type_name)?)));
+
+        let mut items = Vec::new();
+        for caps in repeated_re.captures_iter(value) {
+            let item = caps.get(1).map_or("", |m| m.as_str());
+            if let Some((type_name, converter)) = converter {
+                self.convert_value(type_name, converter.as_ref(), item)?;
+            }
+            items.push(item.to_string());
+        }
+        Ok(items)
     }
 
     fn process_captures(&self, caps: &regex::Captures) -> Result<ParseResult, ParseError> {
-        let mut converted = Vec::with_capacity(self.field_map.len());
+        self.capture_fields(caps, None)
+            .map_err(|failure| failure.error)
+    }
+
+    // With `errors`, conversion failures are recorded there by field index
+    // instead of ending the capture
+    fn capture_fields(
+        &self,
+        caps: &regex::Captures,
+        mut errors: Option<&mut Vec<(usize, ParseError)>>,
+    ) -> Result<ParseResult, FieldFailure> {
+        let mut converted: Vec<Value> = Vec::with_capacity(self.field_map.len());
+        let mut raw = Vec::with_capacity(self.field_map.len());
+        let mut field_spans = Vec::with_capacity(self.field_map.len());
+        let mut type_keys = Vec::with_capacity(self.field_map.len());
         let mut field_map = HashMap::new();
 
+        let whole = caps.get(0).unwrap();
         for i in 0..caps.len() - 1 {
-            // Skip group 0 (whole match)
-            if let Some(m) = caps.get(i + 1) {
-                let value = m.as_str();
+            // Find the field name for this group index, skipping groups
+            // nested inside a field's own pattern
+            let Some(field_name) = self
+                .field_map
+                .iter()
+                .find(|(_, &idx)| idx == i + 1)
+                .map(|(name, _)| name.clone())
+            else {
+                continue;
+            };
 
-                // Find the field name for this group index
-                let field_name = self
-                    .field_map
-                    .iter()
-                    .find(|(_, &idx)| idx == i + 1)
-                    .map(|(name, _)| name.clone())
-                    .unwrap();
-
-                // Convert value if type is specified
-                if let Some(type_name) = self.field_types.get(&field_name) {
-                    if let Some(converter) = self.type_converters.get(type_name) {
-                        match converter.convert(value) {
-                            Ok(converted_value) => {
-                                field_map.insert(field_name.clone(), converted.len());
-                                converted.push(converted_value);
-                            }
-                            Err(e) => return Err(e),
-                        }
+            // Skip group 0 (whole match). An absent field with a default
+            // takes the default, but has no span of its own.
+            let matched = caps
+                .get(i + 1)
+                .map(|m| (m.as_str(), Some((m.start(), m.end()))))
+                .or_else(|| {
+                    let default = self.defaults.get(&field_name)?;
+                    Some((default.as_str(), None))
+                });
+            let Some((value, span)) = matched else {
+                // Otherwise an absent field is left out, or with `absent_as_empty`
+                // kept as an empty string so positions stay fixed
+                if self.absent_as_empty {
+                    field_map.insert(field_name, converted.len());
+                    converted.push(Value::Shared(Arc::new(String::new())));
+                    raw.push(String::new());
+                    field_spans.push(None);
+                    type_keys.push(None);
+                }
+                continue;
+            };
+            let cased = match self.case_modifiers.get(&field_name) {
+                Some(CaseModifier::Lower) => Cow::Owned(value.to_lowercase()),
+                Some(CaseModifier::Upper) => Cow::Owned(value.to_uppercase()),
+                None => Cow::Borrowed(value),
+            };
+            let value = &*cased;
+
+            let converted_value =
+                if let Some(repeated_re) = self.repeated.get(&field_name) {
+                    self.collect_repeated(&field_name, repeated_re, value)
+                        .map(|items| Value::Shared(Arc::new(items)))
+                } else if let Some(sub_parser) = self.sub_parsers.get(&field_name) {
+                    let mut records = sub_parser.findall(value);
+                    let start = span.map_or(0, |(start, _)| start);
+                    for record in &mut records {
+                        record.offset_spans(start);
                     }
+                    Ok(Value::Local(Rc::new(records)))
                 } else {
-                    // No type specified, store as string
-                    field_map.insert(field_name.clone(), converted.len());
-                    converted.push(Box::new(value.to_string()));
+                    // Convert value if type is specified, otherwise store as string
+                    match self.field_types.get(&field_name).and_then(|type_name| {
+                        Some((type_name, self.type_converters.get(type_name)?))
+                    }) {
+                        Some((type_name, converter)) => {
+                            self.convert_value(type_name, converter.as_ref(), value)
+                        }
+                        None => Ok(Value::Shared(Arc::new(value.to_string()))),
+                    }
+                };
+
+            let converted_value = match (converted_value, errors.as_deref_mut()) {
+                (Ok(converted_value), _) => converted_value,
+                // When collecting errors, a field that fails to convert
+                // keeps its text so later fields stay at their positions
+                (Err(error), Some(errors)) => {
+                    errors.push((converted.len(), error));
+                    Value::Shared(Arc::new(value.to_string()))
+                }
+                (Err(error), None) => {
+                    return Err(FieldFailure {
+                        index: converted.len(),
+                        name: field_name,
+                        value: value.to_string(),
+                        error,
+                    })
                 }
+            };
+            field_map.insert(field_name.clone(), converted.len());
+            converted.push(converted_value);
+            raw.push(value.to_string());
+            field_spans.push(span);
+            type_keys.push(self.field_types.get(&field_name).cloned());
+        }
+
+        // A `{close=open}` field must have matched the same text as `open`
+        for (name, target) in &self.references {
+            let (Some(&index), Some(&target_index)) = (field_map.get(name), field_map.get(target))
+            else {
+                continue;
+            };
+            let same = if self.case_sensitive {
+                raw[index] == raw[target_index]
+            } else {
+                raw[index].to_lowercase() == raw[target_index].to_lowercase()
+            };
+            if !same {
+                return Err(FieldFailure {
+                    index,
+                    name: name.clone(),
+                    value: raw[index].clone(),
+                    error: ParseError::NoMatch,
+                });
+            }
+        }
+
+        // A repeated `{tag}` must have matched the same text each time
+        for (group, name) in &self.backreferences {
+            let first = self.field_map.get(name).and_then(|&first| caps.get(first));
+            let (Some(repeat), Some(first)) = (caps.get(*group), first) else {
+                continue;
+            };
+            let same = if self.case_sensitive {
+                repeat.as_str() == first.as_str()
+            } else {
+                repeat.as_str().to_lowercase() == first.as_str().to_lowercase()
+            };
+            if !same {
+                return Err(FieldFailure {
+                    index: field_map.get(name).copied().unwrap_or(converted.len()),
+                    name: name.clone(),
+                    value: repeat.as_str().to_string(),
+                    error: ParseError::NoMatch,
+                });
             }
         }
 
         Ok(ParseResult {
             converted,
+            raw,
             field_map,
+            span: (whole.start(), whole.end()),
+            field_spans,
+            type_keys,
         })
     }
 
+    // Explain the outcome of `parse`: no match, a field that failed to
+    // convert, or success
+    pub fn debug_match(&self, text: &str) -> MatchDiagnosis {
+        let text = &*self.normalized(text);
+        if self.literal.is_some() {
+            return match self.parse(text) {
+                Some(_) => MatchDiagnosis::Matched,
+                None => MatchDiagnosis::NoMatch,
+            };
+        }
+        let Some(caps) = self.exact_re.captures(text) else {
+            return self.diagnose_divergence(text);
+        };
+        match self.capture_fields(&caps, None) {
+            Ok(_) => MatchDiagnosis::Matched,
+            Err(failure) => MatchDiagnosis::ConversionFailed {
+                index: failure.index,
+                name: failure.name,
+                value: failure.value,
+                reason: failure.error.to_string(),
+            },
+        }
+    }
+
+    // How many leading fields the input agrees with, found by matching ever
+    // longer prefixes of the format
+    fn diagnose_divergence(&self, text: &str) -> MatchDiagnosis {
+        let mut matched_fields = 0;
+        for prefix in &self.field_prefixes {
+            let matches = RegexBuilder::new(&format!("^(?:{})", prefix))
+                .case_insensitive(!self.case_sensitive)
+                .build()
+                .is_ok_and(|prefix_re| prefix_re.is_match(text));
+            if !matches {
+                break;
+            }
+            matched_fields += 1;
+        }
+        if matched_fields == 0 {
+            MatchDiagnosis::NoMatch
+        } else {
+            MatchDiagnosis::Diverged { matched_fields }
+        }
+    }
+
     fn parse_format(
         format: &str,
         type_converters: &HashMap<String, Box<dyn TypeConverter>>,
     ) -> Result<FormatParseResult, ParseError> {
         let mut field_map = HashMap::new();
-        let mut field_types = HashMap::new();
+        let mut field_types: HashMap<String, String> = HashMap::new();
+        let mut repeated = HashMap::new();
+        let mut defaults = HashMap::new();
+        let mut field_prefixes = Vec::new();
+        let mut case_modifiers = HashMap::new();
+        let mut references = HashMap::new();
+        let mut backreferences = Vec::new();
+        let mut sub_formats = HashMap::new();
+        // Each field's pattern, for fields that refer back to it
+        let mut field_patterns: HashMap<String, String> = HashMap::new();
+        // Fields are numbered by position, while group_count tracks regex groups
+        let mut field_count = 0;
         let mut group_count = 0;
+        let mut has_alternatives = false;
+        // Where the pattern ended after the latest untyped field, to tell
+        // whether a branch of the format ends with one
+        let mut untyped_end = None;
+        let mut trailing_untyped = false;
 
         let mut in_field = false;
         let mut in_type = false;
+        let mut in_choice = false;
+        let mut quoted_name = false;
+        let mut inline_regex: Option<String> = None;
+        let mut sub_format: Option<String> = None;
         let mut current_field = String::new();
         let mut current_type = String::new();
         let mut chars = format.chars().peekable();
@@ -441,6 +2443,87 @@ impl Parser {
 
         while let Some(c) = chars.next() {
             match c {
+                // Inside a `{name=[...]}` choice every character is literal
+                _ if in_choice => {
+                    if c == ']' {
+                        in_choice = false;
+                    }
+                    current_field.push(c);
+                }
+                // A `{"full name":w}` field name is taken verbatim
+                '"' if in_field && !in_type && !quoted_name && current_field.is_empty() => {
+                    for c in chars.by_ref() {
+                        if c == '"' {
+                            quoted_name = true;
+                            break;
+                        }
+                        current_field.push(c);
+                    }
+                    if !quoted_name
+                        || current_field.is_empty()
+                        || !matches!(chars.peek(), Some(':' | '}'))
+                    {
+                        return Err(ParseError::InvalidFormat);
+                    }
+                }
+                // A `{id:/[A-Z]{3}-\d+/}` type is a regex used as is
+                '/' if in_field && in_type && current_type.is_empty() && inline_regex.is_none() => {
+                    let mut regex = String::new();
+                    let mut closed = false;
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' => {
+                                regex.push(c);
+                                regex.extend(chars.next());
+                            }
+                            '/' => {
+                                closed = true;
+                                break;
+                            }
+                            _ => regex.push(c),
+                        }
+                    }
+                    // Capturing groups would be mistaken for fields
+                    let valid = Regex::new(&regex).is_ok_and(|re| re.captures_len() == 1);
+                    if !closed || regex.is_empty() || !valid || chars.peek() != Some(&'}') {
+                        return Err(ParseError::InvalidFormat);
+                    }
+                    inline_regex = Some(regex);
+                }
+                // A `{entries:[{k:w}={v:w}]}` type is a format of its own
+                '[' if in_field && in_type && current_type.is_empty() && sub_format.is_none() => {
+                    let mut inner = String::new();
+                    let mut depth = 0;
+                    let mut closed = false;
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '{' => depth += 1,
+                            '}' if depth == 0 => return Err(ParseError::InvalidFormat),
+                            '}' => depth -= 1,
+                            ']' if depth == 0 && chars.peek() == Some(&'}') => {
+                                closed = true;
+                                break;
+                            }
+                            _ => {}
+                        }
+                        inner.push(c);
+                    }
+                    if !closed || !inner.contains('{') {
+                        return Err(ParseError::InvalidFormat);
+                    }
+                    sub_format = Some(inner);
+                }
+                '[' if in_field && !in_type && current_field.ends_with('=') => {
+                    in_choice = true;
+                    current_field.push(c);
+                }
+                // `{|}` separates alternative formats
+                '{' if !in_field && chars.clone().take(2).eq(['|', '}']) => {
+                    chars.nth(1);
+                    trailing_untyped |= untyped_end == Some(pattern.len());
+                    pattern.push('|');
+                    has_alternatives = true;
+                }
                 '{' => {
                     if chars.peek() == Some(&'{') {
                         chars.next();
@@ -451,6 +2534,9 @@ impl Parser {
                         }
                         in_field = true;
                         in_type = false;
+                        quoted_name = false;
+                        inline_regex = None;
+                        sub_format = None;
                         current_field.clear();
                         current_type.clear();
                         brace_count += 1;
@@ -463,40 +2549,319 @@ impl Parser {
                     } else if in_field {
                         in_field = false;
                         in_type = false;
+                        field_count += 1;
                         group_count += 1;
                         brace_count -= 1;
 
+                        // A `{name=[,;]}` field captures whichever listed character matched
+                        let choice_pattern = match current_field.split_once("=[") {
+                            Some((name, choices)) if !quoted_name => {
+                                let choices = choices
+                                    .strip_suffix(']')
+                                    .filter(|choices| !choices.is_empty())
+                                    .ok_or(ParseError::InvalidFormat)?;
+                                if !current_type.is_empty() {
+                                    return Err(ParseError::InvalidFormat);
+                                }
+                                let alternatives: Vec<String> = choices
+                                    .chars()
+                                    .map(|c| regex::escape(&c.to_string()))
+                                    .collect();
+                                current_field = name.to_string();
+                                Some(alternatives.join("|"))
+                            }
+                            _ => None,
+                        };
+
+                        // A `{close=open}` field has to repeat what the earlier
+                        // `open` field matched, and shares its pattern and type
+                        let reference_pattern = match current_field.split_once('=') {
+                            Some((name, target)) if choice_pattern.is_none() && !quoted_name => {
+                                if !current_type.is_empty() {
+                                    return Err(ParseError::InvalidFormat);
+                                }
+                                let target_pattern = field_patterns
+                                    .get(target)
+                                    .ok_or(ParseError::InvalidFormat)?
+                                    .clone();
+                                if let Some(target_type) = field_types.get(target) {
+                                    current_type = target_type.clone();
+                                }
+                                let name = name.to_string();
+                                references.insert(name.clone(), target.to_string());
+                                current_field = name;
+                                Some(target_pattern)
+                            }
+                            _ => None,
+                        };
+
+                        // A `{port:d=8080}` field may be absent, and then takes the default
+                        let default = match current_type.split_once('=') {
+                            Some((type_name, default)) => {
+                                let default = default.to_string();
+                                current_type = type_name.to_string();
+                                Some(default)
+                            }
+                            None => None,
+                        };
+
+                        // `{n:d:unsigned}` matches digits only, leaving a sign just
+                        // before it to the literal text
+                        let unsigned = match current_type.strip_suffix(":unsigned") {
+                            Some(type_name) => {
+                                current_type = type_name.to_string();
+                                true
+                            }
+                            None => false,
+                        };
+
+                        // `{name:w:lower}` and `{name:w:upper}` change the case of the match
+                        let case_modifier = match current_type.rsplit_once(':') {
+                            Some((type_name, modifier)) => {
+                                let modifier = match modifier {
+                                    "lower" => CaseModifier::Lower,
+                                    "upper" => CaseModifier::Upper,
+                                    _ => return Err(ParseError::InvalidFormat),
+                                };
+                                current_type = type_name.to_string();
+                                Some(modifier)
+                            }
+                            None => None,
+                        };
+
+                        // A `{speed:d[kph]}` type accepts an optional unit after the value
+                        let unit = match current_type.split_once('[') {
+                            Some((type_name, unit)) => {
+                                let unit = unit
+                                    .strip_suffix(']')
+                                    .filter(|unit| !unit.is_empty())
+                                    .ok_or(ParseError::InvalidFormat)?;
+                                let unit = regex::escape(unit);
+                                current_type = type_name.to_string();
+                                Some(unit)
+                            }
+                            None => None,
+                        };
+
+                        // A bare count such as `{data:16}` captures exactly that many characters
+                        let fixed_pattern = if !current_type.is_empty()
+                            && current_type.chars().all(|c| c.is_ascii_digit())
+                        {
+                            let count = current_type
+                                .parse::<usize>()
+                                .ok()
+                                .filter(|&count| count > 0)
+                                .ok_or(ParseError::InvalidFormat)?;
+                            current_type.clear();
+                            Some(format!(".{{{}}}", count))
+                        } else {
+                            None
+                        };
+
+                        // A sized type such as `{id:4d}` or `{:.2f}` matches exactly
+                        // that size, unless a converter is registered under the
+                        // whole key (`{:2fa}`)
+                        let sized_type = if type_converters.contains_key(&current_type) {
+                            None
+                        } else {
+                            Self::sized_type(&current_type)?
+                        };
+                        let sized_pattern = match sized_type {
+                            Some((type_name, sized_pattern)) => {
+                                current_type = type_name;
+                                Some(sized_pattern)
+                            }
+                            None => None,
+                        };
+
+                        if unsigned && (current_type != "d" || sized_pattern.is_some()) {
+                            return Err(ParseError::InvalidFormat);
+                        }
+
                         // Get the pattern for the current type
-                        let type_pattern = if !current_type.is_empty() {
+                        let mut untyped = false;
+                        let type_pattern = if sub_format.is_some() {
+                            r".*?"
+                        } else if let Some(inline_regex) = &inline_regex {
+                            inline_regex.as_str()
+                        } else if let Some(reference_pattern) = &reference_pattern {
+                            reference_pattern.as_str()
+                        } else if let Some(choice_pattern) = &choice_pattern {
+                            choice_pattern.as_str()
+                        } else if let Some(fixed_pattern) = &fixed_pattern {
+                            fixed_pattern.as_str()
+                        } else if let Some(sized_pattern) = &sized_pattern {
+                            sized_pattern.as_str()
+                        } else if unsigned {
+                            r"\d+"
+                        } else if !current_type.is_empty() {
                             if let Some(converter) = type_converters.get(&current_type) {
-                                converter.get_pattern().unwrap_or(r".*?")
+                                converter.get_pattern().unwrap_or(
+                                    if converter.is_whitespace_sensitive() {
+                                        r".*?"
+                                    } else {
+                                        r"\S+"
+                                    },
+                                )
                             } else {
                                 return Err(ParseError::InvalidFormat);
                             }
                         } else {
+                            untyped = true;
                             r".*?"
                         };
 
                         // Add to field map before adding pattern
                         let field_name = if current_field.is_empty() {
-                            (group_count - 1).to_string()
+                            (field_count - 1).to_string()
                         } else {
                             current_field.clone()
                         };
 
+                        // A name used again, as in `<{tag}>...</{tag}>`, must match
+                        // the same text as its first occurrence
+                        if !current_field.is_empty() && field_map.contains_key(&field_name) {
+                            let same_type = current_type.is_empty()
+                                || field_types.get(&field_name) == Some(&current_type);
+                            if !same_type
+                                || default.is_some()
+                                || case_modifier.is_some()
+                                || unit.is_some()
+                            {
+                                return Err(ParseError::InvalidFormat);
+                            }
+                            let first_pattern = field_patterns[&field_name].clone();
+                            backreferences.push((group_count, field_name));
+                            group_count += Regex::new(&first_pattern)
+                                .map_err(|_| ParseError::InvalidFormat)?
+                                .captures_len()
+                                - 1;
+                            pattern.push_str(&format!("({})", first_pattern));
+                            field_prefixes.push(pattern.clone());
+                            // The occurrence is not a field of its own, so it
+                            // takes no position
+                            field_count -= 1;
+                            continue;
+                        }
+
                         field_map.insert(field_name.clone(), group_count);
+                        field_patterns.insert(field_name.clone(), type_pattern.to_string());
+                        if let Some(sub_format) = sub_format.take() {
+                            if default.is_some() || case_modifier.is_some() || unit.is_some() {
+                                return Err(ParseError::InvalidFormat);
+                            }
+                            sub_formats.insert(field_name.clone(), sub_format);
+                        }
+                        if let Some(case_modifier) = case_modifier {
+                            case_modifiers.insert(field_name.clone(), case_modifier);
+                        }
+                        if let Some(default) = &default {
+                            // The default has to be a valid value of the field's type
+                            let converter = type_converters
+                                .get(&current_type)
+                                .ok_or(ParseError::InvalidFormat)?;
+                            converter
+                                .convert(default)
+                                .map_err(|_| ParseError::InvalidFormat)?;
+                            defaults.insert(field_name.clone(), default.clone());
+                        }
                         if !current_type.is_empty() {
                             field_types.insert(field_name, current_type.clone());
                         }
 
+                        // Groups inside a converter's pattern are nested in the field's group
+                        let inner_groups = Regex::new(type_pattern)
+                            .map_err(|_| ParseError::InvalidFormat)?
+                            .captures_len()
+                            - 1;
+                        group_count += inner_groups;
+
                         pattern.push_str(&format!("({})", type_pattern));
+                        if default.is_some() {
+                            pattern.push('?');
+                        }
+                        if let Some(unit) = unit {
+                            pattern.push_str(&format!("(?:{})?", unit));
+                        }
+                        if untyped {
+                            untyped_end = Some(pattern.len());
+                        }
+                        field_prefixes.push(pattern.clone());
                     } else {
                         return Err(ParseError::InvalidFormat);
                     }
                 }
+                // A second colon starts a modifier such as `{name:w:lower}`
+                ':' if in_field && in_type => {
+                    current_type.push(c);
+                }
                 ':' if in_field => {
                     in_type = true;
                 }
+                // `( {arg:w})*` repeats a single field zero or more times
+                '(' if !in_field => {
+                    let rest: String = chars.clone().collect();
+                    let Some(inner) = Self::repeat_group(&rest) else {
+                        pattern.push_str("\\(");
+                        continue;
+                    };
+                    for _ in 0..inner.chars().count() + 2 {
+                        chars.next();
+                    }
+
+                    let (
+                        inner_pattern,
+                        _,
+                        inner_map,
+                        inner_types,
+                        inner_repeated,
+                        inner_defaults,
+                        _,
+                        _,
+                        _,
+                        _,
+                        inner_sub_formats,
+                        _,
+                    ) = Self::parse_format(inner, type_converters)?;
+                    if inner_map.len() != 1
+                        || !inner_repeated.is_empty()
+                        || !inner_defaults.is_empty()
+                        || !inner_sub_formats.is_empty()
+                    {
+                        return Err(ParseError::InvalidFormat);
+                    }
+                    let inner_name = inner_map.into_keys().next().unwrap();
+                    let field_name = if inner_name.parse::<usize>().is_ok() {
+                        field_count.to_string()
+                    } else {
+                        inner_name.clone()
+                    };
+                    field_count += 1;
+                    group_count += 1;
+                    field_map.insert(field_name.clone(), group_count);
+                    if let Some(inner_type) = inner_types.get(&inner_name) {
+                        field_types.insert(field_name.clone(), inner_type.clone());
+                    }
+
+                    // Groups inside the repetition are nested in the field's group
+                    let inner_groups = Regex::new(&inner_pattern)
+                        .map_err(|_| ParseError::InvalidFormat)?
+                        .captures_len()
+                        - 1;
+                    group_count += inner_groups;
+                    pattern.push_str(&format!("((?:{})*)", inner_pattern));
+                    repeated.insert(field_name, inner_pattern);
+                    field_prefixes.push(pattern.clone());
+                }
+                // `*` skips any run of text; `**` is a literal asterisk
+                '*' if !in_field => {
+                    if chars.peek() == Some(&'*') {
+                        chars.next();
+                        pattern.push_str("\\*");
+                    } else {
+                        pattern.push_str(".*?");
+                    }
+                }
                 _ => {
                     if in_field {
                         if in_type {
@@ -529,30 +2894,493 @@ impl Parser {
         if brace_count != 0 || in_field {
             return Err(ParseError::InvalidFormat);
         }
+        trailing_untyped |= untyped_end == Some(pattern.len());
+
+        // Keep the anchors applying to every branch
+        if has_alternatives {
+            pattern = format!("(?:{})", pattern);
+        }
+
+        Ok((
+            pattern.clone(),
+            pattern,
+            field_map,
+            field_types,
+            repeated,
+            defaults,
+            field_prefixes,
+            case_modifiers,
+            references,
+            backreferences,
+            sub_formats,
+            trailing_untyped,
+        ))
+    }
+
+    // A type with a leading width and/or precision, as in `{id:4d}` or
+    // `{:.2f}`: the type key and a pattern for exactly that size. Only the
+    // built-in numeric and word types can be sized.
+    fn sized_type(spec: &str) -> Result<Option<(String, String)>, ParseError> {
+        let type_start = spec
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(spec.len());
+        let (size, type_name) = spec.split_at(type_start);
+        if size.is_empty() || type_name.is_empty() {
+            return Ok(None);
+        }
+
+        let count = |s: &str| {
+            s.parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or(ParseError::InvalidFormat)
+        };
+        let (width, precision) = match size.split_once('.') {
+            Some(("", precision)) => (None, Some(count(precision)?)),
+            Some((width, precision)) => (Some(count(width)?), Some(count(precision)?)),
+            None => (Some(count(size)?), None),
+        };
+
+        let pattern = match (type_name, width, precision) {
+            ("d", Some(width), None) => format!(r"\d{{{}}}", width),
+            ("w", Some(width), None) => format!(r"\w{{{}}}", width),
+            ("x", Some(width), None) => format!(r"[0-9a-fA-F]{{{}}}", width),
+            ("o", Some(width), None) => format!(r"[0-7]{{{}}}", width),
+            ("b", Some(width), None) => format!(r"[01]{{{}}}", width),
+            ("f", Some(width), None) => format!(r"[-\d.]{{{}}}", width),
+            ("f", None, Some(precision)) => format!(r"-?\d+\.\d{{{}}}", precision),
+            ("f", Some(width), Some(precision)) if width > precision + 1 => {
+                format!(r"[-\d]{{{}}}\.\d{{{}}}", width - precision - 1, precision)
+            }
+            _ => return Err(ParseError::InvalidFormat),
+        };
+        Ok(Some((type_name.to_string(), pattern)))
+    }
+
+    // The body of a `(...)*` repeat group at the start of `rest`, if it is one
+    fn repeat_group(rest: &str) -> Option<&str> {
+        let end = rest.find(")*")?;
+        let inner = &rest[..end];
+        if rest[end + 2..].starts_with('*') || inner.contains(['(', ')']) || !inner.contains('{') {
+            return None;
+        }
+        Some(inner)
+    }
+
+    // The `TypeId` a field's value is boxed as, when it is known up front
+    pub fn field_type_id(&self, name: &str) -> Option<TypeId> {
+        if !self.field_map.contains_key(name) {
+            return None;
+        }
+        if self.repeated.contains_key(name) {
+            return Some(TypeId::of::<Vec<String>>());
+        }
+        if self.sub_parsers.contains_key(name) {
+            return Some(TypeId::of::<Vec<ParseResult>>());
+        }
+        match self.field_types.get(name) {
+            Some(type_name) => self.type_converters.get(type_name)?.output_type_id(),
+            None => Some(TypeId::of::<String>()),
+        }
+    }
 
-        Ok((pattern.clone(), pattern, field_map, field_types))
+    fn normalized<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.normalize_whitespace {
+            Cow::Owned(collapse_whitespace(text))
+        } else {
+            Cow::Borrowed(text)
+        }
     }
 
     pub fn parse(&self, text: &str) -> Option<ParseResult> {
-        self.exact_re
-            .captures(text)
-            .and_then(|captures| self.process_captures(&captures).ok())
+        self.try_parse(text).ok()
     }
 
     pub fn search(&self, text: &str) -> Option<ParseResult> {
-        self.search_re
-            .captures(text)
-            .and_then(|captures| self.process_captures(&captures).ok())
+        self.try_search(text).ok()
+    }
+
+    // Like `parse`, but tells a failed match (`ParseError::NoMatch`) apart
+    // from a field that matched and then failed to convert
+    pub fn try_parse(&self, text: &str) -> Result<ParseResult, ParseError> {
+        let text = &*self.normalized(text);
+        if let Some(literal) = &self.literal {
+            return self.parse_literal(literal, text).ok_or(ParseError::NoMatch);
+        }
+        let captures = self.exact_re.captures(text).ok_or(ParseError::NoMatch)?;
+        self.process_captures(&captures)
+    }
+
+    // Like `try_parse`, but converts every field instead of stopping at the
+    // first failure. Fields that fail keep their matched text as a `String`
+    // and are listed with their index and error.
+    pub fn try_parse_collect_errors(
+        &self,
+        text: &str,
+    ) -> Result<(ParseResult, Vec<(usize, ParseError)>), ParseError> {
+        let text = &*self.normalized(text);
+        if let Some(literal) = &self.literal {
+            let result = self
+                .parse_literal(literal, text)
+                .ok_or(ParseError::NoMatch)?;
+            return Ok((result, Vec::new()));
+        }
+        let captures = self.exact_re.captures(text).ok_or(ParseError::NoMatch)?;
+        let mut errors = Vec::new();
+        let result = self
+            .capture_fields(&captures, Some(&mut errors))
+            .map_err(|failure| failure.error)?;
+        Ok((result, errors))
+    }
+
+    // Like `search`, but tells a failed match (`ParseError::NoMatch`) apart
+    // from a field that matched and then failed to convert
+    pub fn try_search(&self, text: &str) -> Result<ParseResult, ParseError> {
+        let text = &*self.normalized(text);
+        self.first_match(text, 0)
+    }
+
+    // The first match from byte `pos` on, or why there is none
+    fn first_match(&self, text: &str, pos: usize) -> Result<ParseResult, ParseError> {
+        if self.isolated_numbers {
+            return self
+                .scan_region(text, pos, text.len())
+                .next()
+                .ok_or(ParseError::NoMatch);
+        }
+        let first = self.candidates(text, pos, text.len()).next();
+        first.unwrap_or(Err(ParseError::NoMatch))
+    }
+
+    // The text before the first match, the match itself, and the text after it
+    pub fn search_parts<'a>(&self, text: &'a str) -> Option<(&'a str, ParseResult, &'a str)> {
+        let result = self.search(text)?;
+        let original_offset = self.original_offset(text);
+        let (start, end) = result.match_span();
+        let (start, end) = (original_offset(start), original_offset(end));
+        Some((text.get(..start)?, result, text.get(end..)?))
+    }
+
+    // Maps a byte offset in the text actually matched (whitespace-collapsed
+    // with `normalize_whitespace`) back to the same place in `text`
+    fn original_offset(&self, text: &str) -> impl Fn(usize) -> usize {
+        let offsets = self.normalize_whitespace.then(|| uncollapsed_offsets(text));
+        move |pos| offsets.as_ref().map_or(pos, |offsets| offsets[pos])
+    }
+
+    // The reverse of `original_offset`: maps a byte offset in `text` to the
+    // same place in the text actually matched. An offset that is out of
+    // bounds or inside a character maps to `usize::MAX`, which no region
+    // accepts.
+    fn matched_offset<'a>(&self, text: &'a str) -> impl Fn(usize) -> usize + 'a {
+        let offsets = self.normalize_whitespace.then(|| uncollapsed_offsets(text));
+        move |pos| match &offsets {
+            Some(_) if !text.is_char_boundary(pos) => usize::MAX,
+            Some(offsets) => offsets.partition_point(|&offset| offset < pos),
+            None => pos,
+        }
+    }
+
+    pub fn search_last(&self, text: &str) -> Option<ParseResult> {
+        self.finditer(text).last()
     }
 
     pub fn findall(&self, text: &str) -> Vec<ParseResult> {
+        self.finditer(text).collect()
+    }
+
+    // Every match as a JSON object (see `ParseResult::to_json`), one per line
+    #[cfg(feature = "serde")]
+    pub fn findall_jsonl(&self, text: &str) -> String {
+        self.finditer(text)
+            .map(|result| result.to_json() + "\n")
+            .collect()
+    }
+
+    // Lazily yield each match `findall` would return
+    pub fn finditer<'a>(&'a self, text: &'a str) -> impl Iterator<Item = ParseResult> + 'a {
+        let results: Box<dyn Iterator<Item = ParseResult> + 'a> = match self.normalized(text) {
+            Cow::Borrowed(text) => Box::new(self.scan(text)),
+            // Whitespace-collapsed input is a temporary, so match it up front
+            Cow::Owned(text) => Box::new(self.scan(&text).collect::<Vec<_>>().into_iter()),
+        };
+        results
+    }
+
+    // Every match in `text` in order, with the non-empty text between them.
+    // The gaps are always sliced from `text` as given.
+    pub fn findall_with_gaps<'a>(&self, text: &'a str) -> Vec<Segment<'a>> {
+        let original_offset = self.original_offset(text);
+        let mut segments = Vec::new();
+        let mut last = 0;
+        for result in self.finditer(text) {
+            let (start, end) = result.span;
+            let (start, end) = (original_offset(start), original_offset(end));
+            if start > last {
+                segments.extend(text.get(last..start).map(Segment::Text));
+            }
+            segments.push(Segment::Match(result));
+            last = end;
+        }
+        if last < text.len() {
+            segments.extend(text.get(last..).map(Segment::Text));
+        }
+        segments
+    }
+
+    // `findall` over at most the first `max_bytes` of `text` (rounded down to
+    // a character boundary), to bound the work done on untrusted input
+    pub fn findall_bounded(&self, text: &str, max_bytes: usize) -> Vec<ParseResult> {
+        let mut end = max_bytes.min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.findall(&text[..end])
+    }
+
+    // Like `search`, but starting at byte `pos`. Spans still count from the
+    // start of `text`, and the text before `pos` is still seen by anchors
+    // such as `\b`. `None` if `pos` is out of bounds or inside a character.
+    pub fn search_from(&self, text: &str, pos: usize) -> Option<ParseResult> {
+        let pos = self.matched_offset(text)(pos);
+        let text = &*self.normalized(text);
+        self.first_match(text, pos).ok()
+    }
+
+    // Like `findall`, but only for matches within the bytes `pos..endpos`,
+    // with spans counted from the start of `text`. Empty if the range is
+    // reversed, out of bounds or splits a character.
+    pub fn findall_in(&self, text: &str, pos: usize, endpos: usize) -> Vec<ParseResult> {
+        let matched_offset = self.matched_offset(text);
+        let (pos, endpos) = (matched_offset(pos), matched_offset(endpos));
+        let text = &*self.normalized(text);
+        self.scan_region(text, pos, endpos).collect()
+    }
+
+    fn scan_region<'a>(
+        &'a self,
+        text: &'a str,
+        pos: usize,
+        endpos: usize,
+    ) -> impl Iterator<Item = ParseResult> + 'a {
+        self.candidates(text, pos, endpos).filter_map(Result::ok)
+    }
+
+    // Each unanchored match within the bytes `pos..endpos`, converted. The
+    // regex has no back-references, so a match whose repeated or
+    // `{close=open}` fields differ is only rejected afterwards, and the scan
+    // moves on past it.
+    fn candidates<'a>(
+        &'a self,
+        text: &'a str,
+        pos: usize,
+        endpos: usize,
+    ) -> impl Iterator<Item = Result<ParseResult, ParseError>> + 'a {
+        let region = text
+            .get(..endpos)
+            .filter(|region| pos <= endpos && region.is_char_boundary(pos));
+        let mut start = pos;
+        std::iter::from_fn(move || {
+            let region = region?;
+            while start <= region.len() {
+                let captures = self.search_re.captures_at(region, start)?;
+                let whole = captures.get(0).unwrap();
+                // Step past an empty match so the search moves on
+                start = if whole.is_empty() {
+                    region[whole.end()..]
+                        .chars()
+                        .next()
+                        .map_or(region.len() + 1, |c| whole.end() + c.len_utf8())
+                } else {
+                    whole.end()
+                };
+                match self.process_captures(&captures) {
+                    Err(ParseError::NoMatch) => continue,
+                    Ok(result) if self.isolated_numbers && Self::is_dotted(&result, text) => {
+                        continue
+                    }
+                    result => return Some(result),
+                }
+            }
+            None
+        })
+    }
+
+    // `findall` keeping only the first result for each distinct set of
+    // matched field texts
+    pub fn findall_unique(&self, text: &str) -> Vec<ParseResult> {
+        let mut seen = HashSet::new();
+        self.findall(text)
+            .into_iter()
+            .filter(|result| seen.insert(result.raw.clone()))
+            .collect()
+    }
+
+    // `findall` with the result vector preallocated for `capacity` matches
+    pub fn findall_with_capacity(&self, text: &str, capacity: usize) -> Vec<ParseResult> {
+        let mut results = Vec::with_capacity(capacity);
+        results.extend(self.finditer(text));
+        results
+    }
+
+    // Every successfully converted, unanchored match in `text`
+    fn scan<'a>(&'a self, text: &'a str) -> impl Iterator<Item = ParseResult> + 'a {
         self.search_re
             .captures_iter(text)
             .filter_map(|captures| self.process_captures(&captures).ok())
+            .filter(move |result| !self.isolated_numbers || !Self::is_dotted(result, text))
+    }
+
+    // Whether a numeric field at the edge of the match continues into a
+    // dotted number outside it, like the `2` in `v1.2.3`
+    fn is_dotted(result: &ParseResult, text: &str) -> bool {
+        let (start, end) = result.span;
+        let before = text[..start].strip_suffix('.');
+        let after = text[end..].strip_prefix('.');
+        let dotted_before = before.is_some_and(|b| b.ends_with(|c: char| c.is_ascii_digit()));
+        let dotted_after = after.is_some_and(|a| a.starts_with(|c: char| c.is_ascii_digit()));
+
+        result
+            .field_spans
+            .iter()
+            .enumerate()
+            .filter_map(|(i, span)| Some((i, (*span)?)))
+            .any(|(i, (field_start, field_end))| {
+                let value = &result.converted[i];
+                let numeric = value.is::<i64>() || value.is::<f64>();
+                numeric
+                    && ((field_start == start && dotted_before)
+                        || (field_end == end && dotted_after))
+            })
+    }
+
+    // Like `findall`, but only whole lines matching the anchored format count
+    pub fn findall_lines_exact(&self, text: &str) -> Vec<ParseResult> {
+        text.split('\n')
+            .filter_map(|line| self.parse(line))
+            .collect()
+    }
+
+    // Parse each blank-line separated record (which may span several lines)
+    // against the whole format, skipping records that don't match. Spans are
+    // relative to the record.
+    pub fn parse_records(&self, text: &str) -> Vec<ParseResult> {
+        let mut records = Vec::new();
+        let mut record: Vec<&str> = Vec::new();
+        for line in text.lines().chain(std::iter::once("")) {
+            if !line.trim().is_empty() {
+                record.push(line);
+            } else if !record.is_empty() {
+                records.extend(self.parse(&record.join("\n")));
+                record.clear();
+            }
+        }
+        records
+    }
+
+    // Split a comma-separated row, honouring double-quoted fields. Independent
+    // of the parser's format.
+    pub fn parse_csv_row(&self, line: &str) -> Vec<String> {
+        split_csv_row(line)
+    }
+
+    // Collect every match of a `{key}={value}` style format into a map, using
+    // the fields named `key` and `value` if present, else the first two fields.
+    pub fn parse_pairs(&self, text: &str) -> HashMap<String, String> {
+        self.finditer(text)
+            .filter_map(|result| {
+                let key = result.index_of("key").unwrap_or(0);
+                let value = result.index_of("value").unwrap_or(1);
+                Some((result.raw.get(key)?.clone(), result.raw.get(value)?.clone()))
+            })
             .collect()
     }
 }
 
+// Quoted fields may contain commas; quotes inside them are escaped as `""` or `\"`
+fn split_csv_row(line: &str) -> Vec<String> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    if line.is_empty() {
+        return Vec::new();
+    }
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '\\' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            // Only an opening quote (after optional spaces) starts a quoted field
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+// Replace each run of whitespace with a single space
+fn collapse_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+// For each byte offset in `collapse_whitespace(s)`, and for its end, the
+// matching offset in `s`. A collapsed space maps to the start of its run.
+fn uncollapsed_offsets(s: &str) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(s.len() + 1);
+    let mut in_space = false;
+    for (offset, c) in s.char_indices() {
+        if c.is_whitespace() {
+            if !in_space {
+                offsets.push(offset);
+            }
+            in_space = true;
+        } else {
+            offsets.extend(offset..offset + c.len_utf8());
+            in_space = false;
+        }
+    }
+    offsets.push(s.len());
+    offsets
+}
+
+// The type keys every parser understands, sorted
+pub fn builtin_types() -> Vec<&'static str> {
+    let mut keys: Vec<&'static str> = DEFAULT_TYPES.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    keys
+}
+
 pub fn parse_with_types(
     format: &str,
     text: &str,
@@ -583,16 +3411,64 @@ pub fn search_with_types(
         .search(text)
 }
 
+lazy_static::lazy_static! {
+    // Parsers compiled by the free functions, keyed by format and case sensitivity
+    static ref PARSER_CACHE: Mutex<LruCache<(String, bool), Arc<Parser>>> =
+        Mutex::new(LruCache::new(PARSER_CACHE_CAPACITY));
+}
+
+// Formats the free functions keep compiled; the least recently used is
+// dropped to make room for another
+const PARSER_CACHE_CAPACITY: usize = 256;
+
+// The cached parser for `format`, compiling it on first use
+fn cached_parser(format: &str) -> Option<Arc<Parser>> {
+    let key = (format.to_string(), false);
+    if let Some(parser) = PARSER_CACHE.lock().unwrap().get(&key) {
+        return Some(parser);
+    }
+    let parser = Arc::new(Parser::new(format, false).ok()?);
+    PARSER_CACHE
+        .lock()
+        .unwrap()
+        .insert(key, Arc::clone(&parser));
+    Some(parser)
+}
+
+impl Parser {
+    // Drop the parsers the free functions (`parse`, `search`, ...) have
+    // compiled and kept for reuse. At most 256 are kept in any case, the
+    // least recently used making way for new formats.
+    pub fn clear_cache() {
+        PARSER_CACHE.lock().unwrap().clear();
+    }
+
+    // Number of parsers currently held by the free functions' cache
+    pub fn cache_len() -> usize {
+        PARSER_CACHE.lock().unwrap().len()
+    }
+}
+
 pub fn parse(format: &str, text: &str) -> Option<ParseResult> {
-    Parser::new(format, false).ok()?.parse(text)
+    cached_parser(format)?.parse(text)
 }
 
 pub fn search(format: &str, text: &str) -> Option<ParseResult> {
-    Parser::new(format, false).ok()?.search(text)
+    cached_parser(format)?.search(text)
 }
 
 pub fn findall(format: &str, text: &str) -> Vec<ParseResult> {
-    Parser::new(format, false)
+    cached_parser(format)
         .map(|p| p.findall(text))
         .unwrap_or_default()
 }
+
+// The first of `texts` that matches `format`, with its index. The format is
+// compiled once for all of them.
+pub fn parse_first(format: &str, texts: &[&str]) -> Option<(usize, ParseResult)> {
+    let parser = cached_parser(format)?;
+    texts
+        .iter()
+        .enumerate()
+        .find_map(|(index, text)| Some((index, parser.parse(text)?)))
+}
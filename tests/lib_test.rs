@@ -1,8 +1,10 @@
 //! Test cases for the parse_rust library
 use chrono::NaiveDateTime;
 use parse_rust::*;
+use std::collections::HashMap;
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -511,4 +513,1908 @@ mod tests {
         let result = parse("Hello {:w}!", "Hello World!\n").unwrap();
         assert_eq!(*result.get::<String>(0).unwrap(), "World");
     }
+
+    #[test]
+    fn test_iso_utc_datetime() {
+        use chrono::{DateTime, Utc};
+
+        // Offsets are converted to the equivalent UTC instant
+        let result = parse("At {:tiu}", "At 2024-12-27T19:57:55+02:00").unwrap();
+        let dt = result.get::<DateTime<Utc>>(0).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-12-27T17:57:55+00:00");
+
+        // Offset-less input is taken to already be UTC
+        let result = parse("At {:tiu}", "At 2024-12-27T19:57:55").unwrap();
+        let dt = result.get::<DateTime<Utc>>(0).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-12-27T19:57:55+00:00");
+    }
+
+    // A converter whose pattern has groups of its own
+    #[derive(Debug, Clone)]
+    struct VersionConverter;
+    impl TypeConverter for VersionConverter {
+        fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+            Ok(Box::new(s.to_string()))
+        }
+        fn get_pattern(&self) -> Option<&str> {
+            Some(r"(\d+)\.(\d+)")
+        }
+    }
+
+    #[test]
+    fn test_parse_pairs() {
+        let p = Parser::new("{key:w}={value:w}", true).unwrap();
+        let pairs = p.parse_pairs("a=1 b=2 c=3");
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs["a"], "1");
+        assert_eq!(pairs["b"], "2");
+        assert_eq!(pairs["c"], "3");
+
+        // Positional fields work too
+        let p = Parser::new("{:w}:{:d}", true).unwrap();
+        let pairs = p.parse_pairs("x:10 y:20");
+        assert_eq!(pairs["x"], "10");
+        assert_eq!(pairs["y"], "20");
+
+        // Groups inside a converter's pattern don't shift the fields
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert("ver".to_string(), Box::new(VersionConverter));
+        let p = Parser::new_with_types("{:ver}={:w}", true, types).unwrap();
+        let pairs = p.parse_pairs("1.2=a, 3.4=b");
+        assert_eq!(pairs["1.2"], "a");
+        assert_eq!(pairs["3.4"], "b");
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let err = IntConverter.convert("99999999999999999999").unwrap_err();
+        assert!(matches!(err, ParseError::IntegerOverflow(ref v) if v == "99999999999999999999"));
+
+        let err = IntConverter.convert("-99999999999999999999").unwrap_err();
+        assert!(matches!(err, ParseError::IntegerOverflow(_)));
+
+        // Non-numeric input is still a plain conversion failure
+        let err = IntConverter.convert("abc").unwrap_err();
+        assert!(matches!(err, ParseError::TypeConversionFailed));
+
+        // An overflowing field does not parse
+        assert!(parse("{:d}", "99999999999999999999").is_none());
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let result = parse("{name:w} is {age:d}", "Alice is 30").unwrap();
+        let owned = result.into_owned();
+
+        let handle = std::thread::spawn(move || {
+            assert_eq!(owned.len(), 2);
+            assert_eq!(owned.get(0), Some("Alice"));
+            assert_eq!(owned.named("age"), Some("30"));
+            assert_eq!(owned.named("missing"), None);
+            owned
+        });
+        let owned = handle.join().unwrap();
+        assert_eq!(owned.get(1), Some("30"));
+    }
+
+    #[test]
+    fn test_findall_lines_exact() {
+        let p = Parser::new("{key:w}={value:d}", true).unwrap();
+        let text = "a=1\nprefix b=2\nc=3 suffix\nd=4\n";
+
+        // findall picks up partial-line matches
+        assert_eq!(p.findall(text).len(), 4);
+
+        let results = p.findall_lines_exact(text);
+        let keys: Vec<&String> = results
+            .iter()
+            .map(|r| r.named::<String>("key").unwrap())
+            .collect();
+        assert_eq!(keys, vec!["a", "d"]);
+    }
+
+    #[test]
+    fn test_temperature() {
+        let p = Parser::new("temp={t:temp}", true).unwrap();
+
+        let result = p.parse("temp=21.5°C").unwrap();
+        let t = result.named::<Temperature>("t").unwrap();
+        assert_eq!(*t, Temperature { value: 21.5, unit: 'C' });
+        assert_eq!(t.to_celsius(), 21.5);
+
+        // Degree symbol is optional, whitespace before the unit is allowed
+        let result = p.parse("temp=212 F").unwrap();
+        let t = result.named::<Temperature>("t").unwrap();
+        assert_eq!(t.unit, 'F');
+        assert_eq!(t.to_celsius(), 100.0);
+
+        let result = p.parse("temp=-10°C").unwrap();
+        let t = result.named::<Temperature>("t").unwrap();
+        assert_eq!(t.value, -10.0);
+        assert_eq!(t.unit, 'C');
+
+        let result = p.parse("temp=300K").unwrap();
+        let t = result.named::<Temperature>("t").unwrap();
+        assert!((t.to_celsius() - 26.85).abs() < 1e-9);
+
+        assert!(p.parse("temp=21.5X").is_none());
+    }
+
+    #[test]
+    fn test_get_range() {
+        let result = parse("{:w}: {:d} {:d} {:d}", "rgb: 10 20 30").unwrap();
+        assert_eq!(result.get_range::<i64>(1..4), Some(vec![10, 20, 30]));
+
+        // Any type mismatch or out-of-range index fails the whole range
+        assert_eq!(result.get_range::<i64>(0..2), None);
+        assert_eq!(result.get_range::<i64>(2..5), None);
+        assert_eq!(result.get_range::<i64>(1..1), Some(vec![]));
+    }
+
+    #[test]
+    fn test_grouped_float() {
+        let p = Parser::new("Total: {:fn} USD", true).unwrap();
+
+        let grouped = p.parse("Total: 1,234.56 USD").unwrap();
+        let plain = p.parse("Total: 1234.56 USD").unwrap();
+        assert_eq!(*grouped.get::<f64>(0).unwrap(), 1234.56);
+        assert_eq!(grouped.get::<f64>(0), plain.get::<f64>(0));
+
+        let result = p.parse("Total: -12,345,678 USD").unwrap();
+        assert_eq!(*result.get::<f64>(0).unwrap(), -12345678.0);
+
+        // Malformed grouping is not accepted
+        assert!(p.parse("Total: 12,34.5 USD").is_none());
+    }
+
+    #[test]
+    fn test_matched_text() {
+        let text = "name=John age=42 color=blue";
+        let result = search("age={:d}", text).unwrap();
+        assert_eq!(result.match_span(), (10, 16));
+        assert_eq!(result.matched_text(text), Some("age=42"));
+        assert_eq!(result.matched_text("age=4"), None);
+    }
+
+    #[test]
+    fn test_captured_separator() {
+        let p = Parser::new("{a:w}{sep=[,;]}{b:w}", true).unwrap();
+
+        let result = p.parse("x;y").unwrap();
+        assert_eq!(*result.named::<String>("sep").unwrap(), ";");
+        assert_eq!(*result.named::<String>("b").unwrap(), "y");
+
+        let result = p.parse("x,y").unwrap();
+        assert_eq!(*result.named::<String>("sep").unwrap(), ",");
+
+        assert!(p.parse("x|y").is_none());
+
+        // Regex metacharacters and colons are taken literally
+        let p = Parser::new("{a:d}{op=[+*:]}{b:d}", true).unwrap();
+        let result = p.parse("3*4").unwrap();
+        assert_eq!(*result.named::<String>("op").unwrap(), "*");
+        let result = p.parse("3:4").unwrap();
+        assert_eq!(*result.named::<String>("op").unwrap(), ":");
+
+        assert!(Parser::new("{sep=[]}", true).is_err());
+        assert!(Parser::new("{sep=[,;}", true).is_err());
+    }
+
+    #[test]
+    fn test_field_type_id() {
+        use std::any::TypeId;
+
+        let p = Parser::new("{id:d} {name:w} {score:f} {note} {:d}", true).unwrap();
+        assert_eq!(p.field_type_id("id"), Some(TypeId::of::<i64>()));
+        assert_eq!(p.field_type_id("name"), Some(TypeId::of::<String>()));
+        assert_eq!(p.field_type_id("score"), Some(TypeId::of::<f64>()));
+        assert_eq!(p.field_type_id("note"), Some(TypeId::of::<String>()));
+        assert_eq!(p.field_type_id("4"), Some(TypeId::of::<i64>()));
+        assert_eq!(p.field_type_id("missing"), None);
+
+        // Datetime families may produce a date, time or datetime
+        let p = Parser::new("{when:tg}", true).unwrap();
+        assert_eq!(p.field_type_id("when"), None);
+    }
+
+    #[test]
+    fn test_field_slices() {
+        let text = "Größe → {name} ünd {size:d}€";
+        let input = "Größe → Maß ünd 42€";
+        let result = parse(text, input).unwrap();
+
+        assert_eq!(result.field_slice(input, "name"), Some("Maß"));
+        assert_eq!(result.get_slice(input, 1), Some("42"));
+        assert_eq!(result.field_slice(input, "missing"), None);
+
+        // Spans are byte offsets, so they account for multi-byte characters
+        let (start, end) = result.spans()[0].unwrap();
+        assert_eq!((start, end), (12, 16));
+        assert_eq!(&input[start..end], "Maß");
+    }
+
+    #[test]
+    fn test_any_datetime() {
+        let p = Parser::new("Logged {when:t}", true).unwrap();
+        let expected = "2024-12-27 19:57:55";
+
+        for input in [
+            "Logged 2024-12-27T19:57:55",
+            "Logged 27/12/2024 19:57:55",
+            "Logged 27/Dec/2024:19:57:55 +0000",
+            "Logged Dec 27 2024 19:57:55",
+        ] {
+            let result = p.parse(input).unwrap();
+            let dt = result.named::<NaiveDateTime>("when").unwrap();
+            assert_eq!(dt.format("%Y-%m-%d %H:%M:%S").to_string(), expected);
+        }
+
+        // Generic (day-first) wins over American for ambiguous dates
+        let result = p.parse("Logged 01/02/2024").unwrap();
+        let dt = result.named::<NaiveDateTime>("when").unwrap();
+        assert_eq!(dt.format("%Y-%m-%d %H:%M").to_string(), "2024-02-01 00:00");
+
+        // American dates that are not valid day-first still parse
+        let result = p.parse("Logged 12/27/2024 07:57:55 PM").unwrap();
+        let dt = result.named::<NaiveDateTime>("when").unwrap();
+        assert_eq!(dt.format("%Y-%m-%d %H:%M:%S").to_string(), expected);
+    }
+
+    #[test]
+    fn test_search_last() {
+        let p = Parser::new("at {:tg}", true).unwrap();
+        let text = "started at 27/12/2024 10:00:00, retried at 27/12/2024 11:00:00, done at 27/12/2024 12:30:00";
+
+        let first = p.search(text).unwrap();
+        let last = p.search_last(text).unwrap();
+        assert_eq!(
+            first.get::<NaiveDateTime>(0).unwrap().format("%H:%M").to_string(),
+            "10:00"
+        );
+        assert_eq!(
+            last.get::<NaiveDateTime>(0).unwrap().format("%H:%M").to_string(),
+            "12:30"
+        );
+
+        assert!(p.search_last("nothing here").is_none());
+    }
+
+    #[test]
+    fn test_literal_wildcard() {
+        let p = Parser::new("start*{id:d}*end", true).unwrap();
+        let result = p.parse("start of request id 42 and the end").unwrap();
+        assert_eq!(*result.named::<i64>("id").unwrap(), 42);
+        assert_eq!(result.into_owned().len(), 1);
+
+        let result = p.parse("start42end").unwrap();
+        assert_eq!(*result.named::<i64>("id").unwrap(), 42);
+
+        // A doubled asterisk matches a literal one
+        let p = Parser::new("{a:d} ** {b:d}", true).unwrap();
+        let result = p.parse("3 * 4").unwrap();
+        assert_eq!(*result.get::<i64>(1).unwrap(), 4);
+        assert!(p.parse("3 x 4").is_none());
+    }
+
+    #[derive(Debug, Clone)]
+    struct YesNoConverter;
+    impl TypeConverter for YesNoConverter {
+        fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+            match s {
+                "yes" => Ok(Box::new(true)),
+                "no" => Ok(Box::new(false)),
+                _ => Err(ParseError::TypeConversionFailed),
+            }
+        }
+
+        fn get_pattern(&self) -> Option<&str> {
+            Some(r"yes|no")
+        }
+    }
+
+    #[test]
+    fn test_default_converters_shared() {
+        // Every built-in type is still available to plain parsers
+        let result = parse(
+            "{:d} {:f} {:fn} {:w} {:temp} {:tg} {:ti}",
+            "1 2.5 3,000 four 5C 27/12/2024 19:57:55 2024-12-27T19:57:55",
+        )
+        .unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), 1);
+        assert_eq!(*result.get::<f64>(2).unwrap(), 3000.0);
+        assert_eq!(*result.get::<String>(3).unwrap(), "four");
+
+        // Extra types are added alongside the defaults
+        let mut extra_types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        extra_types.insert("yn".to_string(), Box::new(YesNoConverter));
+        let boxed: Box<dyn TypeConverter> = Box::new(YesNoConverter);
+        extra_types.insert("bool".to_string(), boxed.clone());
+
+        let result =
+            parse_with_types("{id:d}: {ok:yn}/{also:bool}", "7: yes/no", extra_types).unwrap();
+        assert_eq!(*result.named::<i64>("id").unwrap(), 7);
+        assert!(*result.named::<bool>("ok").unwrap());
+        assert!(!*result.named::<bool>("also").unwrap());
+    }
+
+    #[test]
+    fn test_signed_hex() {
+        let p = Parser::new("r0={:x8s}", true).unwrap();
+
+        let result = p.parse("r0=0000002A").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), 42);
+
+        let result = p.parse("r0=0xFFFFFFFE").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), -2);
+
+        let result = p.parse("r0=80000000").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), i32::MIN as i64);
+
+        // Other widths
+        let result = parse("{:x2s} {:x4s} {:x16s}", "ff 7fff 8000000000000000").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), -1);
+        assert_eq!(*result.get::<i64>(1).unwrap(), 32767);
+        assert_eq!(*result.get::<i64>(2).unwrap(), i64::MIN);
+
+        // The digit count is exact
+        assert!(p.parse("r0=FFFE").is_none());
+    }
+
+    #[test]
+    fn test_literal_only_format() {
+        let p = Parser::new("ready {{ok}}", true).unwrap();
+        let result = p.parse("ready {ok}").unwrap();
+        assert!(result.into_owned().is_empty());
+        assert!(p.parse("ready {ok}\n").is_some());
+        assert!(p.parse("READY {ok}").is_none());
+        assert!(p.parse("ready {ok} now").is_none());
+        assert!(p.parse("ready").is_none());
+
+        let p = Parser::new("ready", false).unwrap();
+        assert_eq!(p.parse("READY  ").unwrap().match_span(), (0, 7));
+        assert!(p.parse("steady").is_none());
+
+        // Flexible punctuation still goes through the regex
+        let p = Parser::new("a,b", true).unwrap();
+        assert!(p.parse("a, b").is_some());
+    }
+
+    #[test]
+    fn test_log_level() {
+        let p = Parser::new("[{lvl:level}] {msg}", true).unwrap();
+        for (input, expected) in [
+            ("[TRACE] x", LogLevel::Trace),
+            ("[debug] x", LogLevel::Debug),
+            ("[Info] x", LogLevel::Info),
+            ("[WARN] x", LogLevel::Warn),
+            ("[WARNING] x", LogLevel::Warn),
+            ("[error] x", LogLevel::Error),
+            ("[ERR] x", LogLevel::Error),
+        ] {
+            let result = p.parse(input).unwrap();
+            assert_eq!(*result.named::<LogLevel>("lvl").unwrap(), expected, "{}", input);
+        }
+
+        assert!(p.parse("[NOTICE] x").is_none());
+        assert!(LogLevel::Warn > LogLevel::Info);
+    }
+
+    #[test]
+    fn test_try_into_tuple() {
+        let result = parse("{:d} {:w}", "42 apples").unwrap();
+        let (count, fruit) = result.try_into_tuple::<(i64, String)>().unwrap();
+        assert_eq!(count, 42);
+        assert_eq!(fruit, "apples");
+
+        // A type mismatch reports the offending position
+        let err = result.try_into_tuple::<(i64, i64)>().unwrap_err();
+        assert!(matches!(err, ParseError::TypeMismatch(1)));
+
+        // So does asking for more fields than were parsed
+        let err = result.try_into_tuple::<(i64, String, f64)>().unwrap_err();
+        assert!(matches!(err, ParseError::TypeMismatch(2)));
+    }
+
+    #[test]
+    fn test_parse_csv_row() {
+        let p = Parser::new("{}", true).unwrap();
+
+        assert_eq!(p.parse_csv_row("a,b,c"), vec!["a", "b", "c"]);
+        assert_eq!(
+            p.parse_csv_row("1,\"Smith, John\",NY\n"),
+            vec!["1", "Smith, John", "NY"]
+        );
+        assert_eq!(
+            p.parse_csv_row(r#""say ""hi""","a \"quoted\" word",,end"#),
+            vec![r#"say "hi""#, r#"a "quoted" word"#, "", "end"]
+        );
+        assert!(p.parse_csv_row("").is_empty());
+    }
+
+    #[test]
+    fn test_shared_values() {
+        use std::sync::Arc;
+
+        let result = parse("{name:w} scored {score:f}", "Alice scored 95.5").unwrap();
+        let first = result.named_arc::<String>("name").unwrap();
+        let second = result.get_arc::<String>(0).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(*first, "Alice");
+        assert!(result.get_arc::<i64>(1).is_none());
+
+        // Cloned results share their values, which can cross threads
+        let copy = result.clone();
+        let score = copy.named_arc::<f64>("score").unwrap();
+        let score = std::thread::spawn(move || *score).join().unwrap();
+        assert_eq!(score, 95.5);
+        assert!(Arc::ptr_eq(&first, &copy.get_arc::<String>(0).unwrap()));
+
+        // Values of converters without `convert_shared` stay in the result
+        let mut extra_types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        extra_types.insert("yn".to_string(), Box::new(YesNoConverter));
+        let result = parse_with_types("{ok:yn}", "yes", extra_types).unwrap();
+        assert!(*result.named::<bool>("ok").unwrap());
+        assert!(result.named_arc::<bool>("ok").is_none());
+    }
+
+    #[test]
+    fn test_optional_unit_suffix() {
+        let p = Parser::new("speed={speed:d[kph]}, limit={limit:f[km/h]}", true).unwrap();
+
+        let result = p.parse("speed=50kph, limit=80.5km/h").unwrap();
+        assert_eq!(*result.named::<i64>("speed").unwrap(), 50);
+        assert_eq!(*result.named::<f64>("limit").unwrap(), 80.5);
+        assert_eq!(result.field_slice("speed=50kph, limit=80.5km/h", "speed"), Some("50"));
+
+        let result = p.parse("speed=50, limit=80.5").unwrap();
+        assert_eq!(*result.named::<i64>("speed").unwrap(), 50);
+        assert_eq!(*result.named::<f64>("limit").unwrap(), 80.5);
+
+        assert!(p.parse("speed=50mph, limit=80").is_none());
+        assert!(Parser::new("{speed:d[]}", true).is_err());
+        assert!(Parser::new("{speed:d[kph}", true).is_err());
+    }
+
+    #[test]
+    fn test_search_parts() {
+        let p = Parser::new("id={:d}", true).unwrap();
+        let (before, result, after) = p.search_parts("user bob id=42 logged in").unwrap();
+        assert_eq!(before, "user bob ");
+        assert_eq!(*result.get::<i64>(0).unwrap(), 42);
+        assert_eq!(after, " logged in");
+
+        assert!(p.search_parts("no id here").is_none());
+    }
+
+    #[test]
+    fn test_strict_trailing_field() {
+        let err = Parser::builder("prefix {rest}")
+            .strict(true)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ParseError::AmbiguousTrailingField));
+
+        // Each alternative's last field is checked
+        for format in ["x {a:d}{|}y {rest}", "x {rest}{|}y {a:d}"] {
+            let err = Parser::builder(format).strict(true).build().unwrap_err();
+            assert!(matches!(err, ParseError::AmbiguousTrailingField), "{}", format);
+        }
+        assert!(Parser::builder("x {a:d}{|}y {rest:w}").strict(true).build().is_ok());
+
+        // Typed, non-trailing or non-strict formats are fine
+        assert!(Parser::builder("prefix {rest:w}")
+            .strict(true)
+            .build()
+            .is_ok());
+        assert!(Parser::builder("prefix {rest}.")
+            .strict(true)
+            .build()
+            .is_ok());
+        let p = Parser::builder("prefix {rest}").build().unwrap();
+        assert_eq!(
+            *p.parse("PREFIX some text  ")
+                .unwrap()
+                .get::<String>(0)
+                .unwrap(),
+            "some text"
+        );
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct CountingConverter {
+        calls: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+    impl TypeConverter for CountingConverter {
+        convert_via_shared!();
+
+        fn convert_shared(
+            &self,
+            s: &str,
+        ) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Box::new(s.to_uppercase()))
+        }
+
+        fn get_pattern(&self) -> Option<&str> {
+            Some(r"[a-z]+")
+        }
+    }
+
+    #[test]
+    fn test_conversion_cache() {
+        use std::sync::atomic::Ordering;
+        use std::sync::Arc;
+
+        let counter = CountingConverter::default();
+        let mut extra_types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        extra_types.insert("status".to_string(), Box::new(counter.clone()));
+
+        let p = Parser::builder("[{:status}]")
+            .types(extra_types)
+            .conversion_cache(2)
+            .build()
+            .unwrap();
+        let results = p.findall("[ok] [ok] [fail] [ok] [fail]");
+        assert_eq!(results.len(), 5);
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 2);
+
+        // Repeated tokens share the same converted value
+        let first = results[0].get_arc::<String>(0).unwrap();
+        assert_eq!(*first, "OK");
+        assert!(Arc::ptr_eq(
+            &first,
+            &results[3].get_arc::<String>(0).unwrap()
+        ));
+
+        // Past capacity the least recently used entry ("ok") is evicted,
+        // so "fail" is still cached but "ok" has to be converted again
+        p.findall("[retry] [fail] [ok]");
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 4);
+        p.findall("[fail] [ok] [retry] [ok]");
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_repeated_trailing_field() {
+        let p = Parser::new("{cmd:w}( {arg:w})*", true).unwrap();
+
+        let result = p.parse("run a b c").unwrap();
+        assert_eq!(*result.named::<String>("cmd").unwrap(), "run");
+        assert_eq!(
+            *result.named::<Vec<String>>("arg").unwrap(),
+            vec!["a", "b", "c"]
+        );
+
+        let result = p.parse("stop").unwrap();
+        assert!(result.named::<Vec<String>>("arg").unwrap().is_empty());
+
+        // Unnamed repeated fields are positional, and keep later positions intact
+        let result = parse("sum( {:d})* = {:d}", "sum 1 2 3 = 6").unwrap();
+        assert_eq!(*result.get::<Vec<String>>(0).unwrap(), vec!["1", "2", "3"]);
+        assert_eq!(*result.get::<i64>(1).unwrap(), 6);
+
+        // Each repetition must still satisfy the field type
+        assert!(parse("sum( {:d})* = {:d}", "sum 1 x 3 = 6").is_none());
+
+        // Parentheses without a trailing `*` stay literal
+        let result = parse("({:w})", "(a)").unwrap();
+        assert_eq!(*result.get::<String>(0).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_debug_match() {
+        let p = Parser::new("{name:w} is {age:d} years", true).unwrap();
+
+        assert_eq!(p.debug_match("Bob is 42 years"), MatchDiagnosis::Matched);
+        assert_eq!(
+            p.debug_match("Bob is old"),
+            MatchDiagnosis::Diverged { matched_fields: 1 }
+        );
+        assert_eq!(p.debug_match("... is 42 years"), MatchDiagnosis::NoMatch);
+        assert_eq!(
+            p.debug_match("Bob is 99999999999999999999 years"),
+            MatchDiagnosis::ConversionFailed {
+                index: 1,
+                name: "age".to_string(),
+                value: "99999999999999999999".to_string(),
+                reason: "integer overflow: 99999999999999999999".to_string(),
+            }
+        );
+    }
+
+    #[cfg(feature = "locale")]
+    #[test]
+    fn test_locale_month_names() {
+        use chrono::NaiveDate;
+
+        let mut extra_types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        extra_types.insert(
+            "date_fr".to_string(),
+            Box::new(LocaleDateConverter::new(Locale::French)),
+        );
+        extra_types.insert(
+            "date_de".to_string(),
+            Box::new(LocaleDateConverter::new(Locale::German)),
+        );
+        let p = Parser::new_with_types("{fr:date_fr} / {de:date_de}", true, extra_types).unwrap();
+        let expected = NaiveDate::from_ymd_opt(2024, 12, 27).unwrap();
+
+        let result = p.parse("27 déc. 2024 / 27. Dezember 2024").unwrap();
+        assert_eq!(*result.named::<NaiveDate>("fr").unwrap(), expected);
+        assert_eq!(*result.named::<NaiveDate>("de").unwrap(), expected);
+
+        // English and numeric dates are still accepted
+        let result = p.parse("27 Dec 2024 / 2024-12-27").unwrap();
+        assert_eq!(*result.named::<NaiveDate>("fr").unwrap(), expected);
+        assert_eq!(*result.named::<NaiveDate>("de").unwrap(), expected);
+
+        assert!(p.parse("27 dezember 2024 / 27 déc 2024").is_none());
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Method {
+        Get,
+        Post,
+    }
+
+    #[derive(Debug, Clone)]
+    struct MethodConverter;
+    impl TypeConverter for MethodConverter {
+        fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+            match s {
+                "GET" => Ok(Box::new(Method::Get)),
+                "POST" => Ok(Box::new(Method::Post)),
+                _ => Err(ParseError::TypeConversionFailed),
+            }
+        }
+    }
+
+    #[test]
+    fn test_reinterpret() {
+        let result = parse("{method:w} {path}", "POST /login").unwrap();
+        assert_eq!(*result.named::<String>("method").unwrap(), "POST");
+        assert_eq!(
+            result.reinterpret::<Method>("method", &MethodConverter),
+            Some(Method::Post)
+        );
+
+        // Conversion failures, wrong types and unknown fields give None
+        assert_eq!(result.reinterpret::<Method>("path", &MethodConverter), None);
+        assert_eq!(result.reinterpret::<i64>("method", &MethodConverter), None);
+        assert_eq!(
+            result.reinterpret::<Method>("missing", &MethodConverter),
+            None
+        );
+        assert_eq!(result.reinterpret::<Method>("0", &MethodConverter), None);
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let p = Parser::new("From: {from}\nSubject: {subject}", true).unwrap();
+        let text =
+            "From: alice\nSubject: hello\n\n\nFrom: bob\r\nSubject: re: hello\n  \nnot a record\n";
+
+        let records = p.parse_records(text);
+        assert_eq!(records.len(), 2);
+        assert_eq!(*records[0].named::<String>("from").unwrap(), "alice");
+        assert_eq!(*records[0].named::<String>("subject").unwrap(), "hello");
+        assert_eq!(*records[1].named::<String>("from").unwrap(), "bob");
+        assert_eq!(*records[1].named::<String>("subject").unwrap(), "re: hello");
+    }
+
+    #[test]
+    fn test_findall_with_capacity() {
+        let p = Parser::new("{:d}", true).unwrap();
+        let text = "1 2 3 4 5";
+
+        let expected: Vec<i64> = p
+            .findall(text)
+            .iter()
+            .map(|r| *r.get::<i64>(0).unwrap())
+            .collect();
+        for capacity in [0, 2, 100] {
+            let results = p.findall_with_capacity(text, capacity);
+            assert!(results.capacity() >= capacity);
+            let numbers: Vec<i64> = results.iter().map(|r| *r.get::<i64>(0).unwrap()).collect();
+            assert_eq!(numbers, expected);
+        }
+    }
+
+    #[test]
+    fn test_system_time() {
+        use std::time::{Duration, SystemTime};
+
+        let p = Parser::new("at {when:systime}", true).unwrap();
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_329_475);
+
+        for input in [
+            "at 2024-12-27T19:57:55",
+            "at 2024-12-27T21:57:55+02:00",
+            "at 27/12/2024 19:57:55",
+        ] {
+            let result = p.parse(input).unwrap();
+            assert_eq!(
+                *result.named::<SystemTime>("when").unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+
+        let result = p.parse("at 1970-01-01").unwrap();
+        assert_eq!(
+            *result.named::<SystemTime>("when").unwrap(),
+            SystemTime::UNIX_EPOCH
+        );
+    }
+
+    #[test]
+    fn test_fixed_length_field() {
+        let p = Parser::new("{data:16}|{rest:4}", true).unwrap();
+        let result = p.parse("ab cd  ef gh ij |1 2 ").unwrap();
+        assert_eq!(*result.named::<String>("data").unwrap(), "ab cd  ef gh ij ");
+        assert_eq!(*result.named::<String>("rest").unwrap(), "1 2 ");
+
+        // Counts are in characters, and must be exact
+        let p = Parser::new("[{:3}]", true).unwrap();
+        assert_eq!(*p.parse("[äöü]").unwrap().get::<String>(0).unwrap(), "äöü");
+        assert!(p.parse("[ab]").is_none());
+        assert!(p.parse("[abcd]").is_none());
+
+        assert!(Parser::new("{data:0}", true).is_err());
+    }
+
+    #[test]
+    fn test_builtin_types() {
+        let keys = builtin_types();
+        #[allow(unused_mut)]
+        let mut expected = vec![
+            "S", "b", "d", "dw", "f", "fn", "hexbytes", "l", "latlon", "level", "nz", "o", "ord",
+            "phone", "range", "systime", "t", "ta", "te", "temp", "tg", "th", "ti", "tiu", "ts",
+            "w", "x", "x16s", "x2s", "x4s", "x8s",
+        ];
+        #[cfg(feature = "base64")]
+        expected.insert(2, "b64");
+        assert_eq!(keys, expected);
+
+        // Every listed key is usable in a format
+        for key in keys {
+            assert!(
+                Parser::new(&format!("{{:{}}}", key), true).is_ok(),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_isolated_numbers() {
+        let text = "v1.2.3 has 4 fixes";
+
+        // By default every digit run is a match
+        let p = Parser::new("{:d}", true).unwrap();
+        assert_eq!(p.findall(text).len(), 4);
+        assert_eq!(*p.search(text).unwrap().get::<i64>(0).unwrap(), 1);
+
+        let p = Parser::builder("{:d}")
+            .isolated_numbers(true)
+            .build()
+            .unwrap();
+        let numbers: Vec<i64> = p
+            .findall(text)
+            .iter()
+            .map(|r| *r.get::<i64>(0).unwrap())
+            .collect();
+        assert_eq!(numbers, vec![4]);
+        assert_eq!(*p.search(text).unwrap().get::<i64>(0).unwrap(), 4);
+
+        // A dot that is part of the format is fine, as is a sentence-ending dot
+        let p = Parser::builder("{:d}.{:d}")
+            .isolated_numbers(true)
+            .build()
+            .unwrap();
+        assert_eq!(p.findall("1.5 and 2.5.1").len(), 1);
+        let p = Parser::builder("{:d} fixes")
+            .isolated_numbers(true)
+            .build()
+            .unwrap();
+        assert!(p.search("it has 4 fixes.").is_some());
+    }
+
+    #[test]
+    fn test_alternative_formats() {
+        let p = Parser::new("id {a:d}{|}name {b:w}", true).unwrap();
+
+        let result = p.parse("id 42").unwrap();
+        assert_eq!(*result.named::<i64>("a").unwrap(), 42);
+        assert!(result.named::<String>("b").is_none());
+
+        let result = p.parse("name bob").unwrap();
+        assert_eq!(*result.named::<String>("b").unwrap(), "bob");
+        assert!(result.named::<i64>("a").is_none());
+
+        // The anchors apply to each branch as a whole
+        assert!(p.parse("id 42 name bob").is_none());
+        assert!(p.parse("id x").is_none());
+
+        // A bare `|` is literal text
+        let p = Parser::new("{:d}|{:d}", true).unwrap();
+        let result = p.parse("1|2").unwrap();
+        assert_eq!(*result.get::<i64>(1).unwrap(), 2);
+        assert!(p.parse("1").is_none());
+    }
+
+    #[test]
+    fn test_latlon_converter() {
+        let p = Parser::new("pos={:latlon}", true).unwrap();
+        let result = p.parse("pos=51.5074, -0.1278").unwrap();
+        assert_eq!(*result.get::<(f64, f64)>(0).unwrap(), (51.5074, -0.1278));
+
+        // Latitude must be within -90..=90
+        assert!(p.parse("pos=91.0,10.0").is_none());
+        assert!(matches!(
+            LatLonConverter.convert("-95.5,10.0"),
+            Err(ParseError::TypeConversionFailed)
+        ));
+        assert!(LatLonConverter.convert("10.0,-181.0").is_err());
+    }
+
+    #[test]
+    fn test_chain_converter() {
+        #[derive(Debug, PartialEq)]
+        struct Port(u16);
+
+        let port = ChainConverter::new(Box::new(IntConverter), |value| {
+            let n = *value.downcast::<i64>().unwrap();
+            let port = u16::try_from(n).map_err(|_| ParseError::TypeConversionFailed)?;
+            Ok(Box::new(Port(port)))
+        });
+        assert_eq!(port.get_pattern(), IntConverter.get_pattern());
+
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert("port".to_string(), Box::new(port));
+        let p = Parser::new_with_types("listening on {:port}", true, types).unwrap();
+
+        let result = p.parse("listening on 8080").unwrap();
+        assert_eq!(result.get::<Port>(0), Some(&Port(8080)));
+        assert!(p.parse("listening on 70000").is_none());
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let p = Parser::new("{x:w} {y:w}", true).unwrap();
+        assert!(p.parse("a    b").is_none());
+
+        let p = Parser::builder("{x:w} {y:w}")
+            .normalize_whitespace(true)
+            .build()
+            .unwrap();
+        let result = p.parse("a    b").unwrap();
+        assert_eq!(*result.named::<String>("x").unwrap(), "a");
+        assert_eq!(*result.named::<String>("y").unwrap(), "b");
+
+        // Whitespace in the format is collapsed too
+        let p = Parser::builder("key:\t  {:d}")
+            .normalize_whitespace(true)
+            .build()
+            .unwrap();
+        assert_eq!(*p.parse("key:\n7").unwrap().get::<i64>(0).unwrap(), 7);
+        assert_eq!(p.findall("key:  1, key:\t2").len(), 2);
+
+        // Every entry point matches the collapsed input
+        let text = "key:  1, key:\t2";
+        assert_eq!(p.findall_with_capacity(text, 4).len(), 2);
+        assert_eq!(*p.search_last(text).unwrap().get::<i64>(0).unwrap(), 2);
+        assert_eq!(p.debug_match("key:\n7"), MatchDiagnosis::Matched);
+        let segments = p.findall_with_gaps(text);
+        assert_eq!(segments.len(), 3);
+        assert!(matches!(segments[1], Segment::Text(", ")));
+
+        let p = Parser::builder("{key:w} = {value:w}")
+            .normalize_whitespace(true)
+            .build()
+            .unwrap();
+        let pairs = p.parse_pairs("a  =  1 b =\t2");
+        assert_eq!(pairs["a"], "1");
+        assert_eq!(pairs["b"], "2");
+
+        // The parts around a match are sliced from the original input
+        let p = Parser::builder("b {:d}")
+            .normalize_whitespace(true)
+            .build()
+            .unwrap();
+        let (before, _, after) = p.search_parts("a    b   5  tail").unwrap();
+        assert_eq!((before, after), ("a    ", "  tail"));
+        let p = Parser::builder("{:d}")
+            .normalize_whitespace(true)
+            .build()
+            .unwrap();
+        let (before, result, after) = p.search_parts("a  \u{e9}5").unwrap();
+        assert_eq!((before, after), ("a  \u{e9}", ""));
+        assert_eq!(*result.get::<i64>(0).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_nonzero_converter() {
+        use std::num::NonZeroU64;
+
+        let p = Parser::new("id={:nz}", true).unwrap();
+        let result = p.parse("id=17").unwrap();
+        assert_eq!(result.get::<NonZeroU64>(0).unwrap().get(), 17);
+
+        assert!(p.parse("id=0").is_none());
+        assert!(matches!(
+            NonZeroConverter.convert("0"),
+            Err(ParseError::TypeConversionFailed)
+        ));
+        assert!(p.parse("id=-3").is_none());
+    }
+
+    #[test]
+    fn test_converter_pattern_with_groups() {
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert("ver".to_string(), Box::new(VersionConverter));
+        let p = Parser::new_with_types("{v:ver} build {n:d} on {host:w}", true, types).unwrap();
+
+        let result = p.parse("1.2 build 345 on alpha").unwrap();
+        assert_eq!(*result.named::<String>("v").unwrap(), "1.2");
+        assert_eq!(*result.named::<i64>("n").unwrap(), 345);
+        assert_eq!(*result.named::<String>("host").unwrap(), "alpha");
+        assert_eq!(result.spans().len(), 3);
+    }
+
+    #[test]
+    fn test_type_keys() {
+        let p = Parser::new("{:d} {name} {:f} {:ti}", true).unwrap();
+        let result = p.parse("1 bob 2.5 2024-12-27").unwrap();
+        assert_eq!(
+            result.type_keys(),
+            vec![Some("d"), None, Some("f"), Some("ti")]
+        );
+
+        let p = Parser::new("no fields", true).unwrap();
+        assert!(p.parse("no fields").unwrap().type_keys().is_empty());
+    }
+
+    #[test]
+    fn test_findall_unique() {
+        let p = Parser::new("from {ip:w}", true).unwrap();
+        let text = "from alpha, from beta, from alpha, from gamma, from beta";
+        assert_eq!(p.findall(text).len(), 5);
+
+        let ips: Vec<String> = p
+            .findall_unique(text)
+            .iter()
+            .map(|r| r.named::<String>("ip").unwrap().clone())
+            .collect();
+        assert_eq!(ips, vec!["alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn test_custom_datetime_format() {
+        let p = Parser::new("at {:tg}", true).unwrap();
+        assert!(p.parse("at 27.12.2024 19:57").is_none());
+
+        let p = Parser::builder("at {:tg}")
+            .datetime_format("tg", "%d.%m.%Y %H:%M")
+            .build()
+            .unwrap();
+        let result = p.parse("at 27.12.2024 19:57").unwrap();
+        let dt: &NaiveDateTime = result.get(0).unwrap();
+        assert_eq!(dt.to_string(), "2024-12-27 19:57:00");
+
+        // Built-in formats still work
+        assert!(p.parse("at 27/12/2024 19:57:55").is_some());
+
+        // Only datetime families can be extended
+        assert!(Parser::builder("{:d}")
+            .datetime_format("d", "%Y")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_hexbytes_converter() {
+        let p = Parser::new("payload: {:hexbytes} end", true).unwrap();
+
+        let result = p.parse("payload: de ad BE ef end").unwrap();
+        assert_eq!(
+            *result.get::<Vec<u8>>(0).unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+
+        let result = p.parse("payload: 0001ff end").unwrap();
+        assert_eq!(*result.get::<Vec<u8>>(0).unwrap(), vec![0x00, 0x01, 0xff]);
+
+        assert!(p.parse("payload: abc end").is_none());
+    }
+
+    #[test]
+    fn test_findall_bounded() {
+        let p = Parser::new("<{:d}>", true).unwrap();
+        let text = "<1> <2> <3> <4>";
+        assert_eq!(p.findall(text).len(), 4);
+        assert_eq!(p.findall_bounded(text, 8).len(), 2);
+        assert_eq!(p.findall_bounded(text, 1000).len(), 4);
+        assert!(p.findall_bounded(text, 0).is_empty());
+
+        // The limit never splits a character
+        assert_eq!(p.findall_bounded("<1>é<2>", 4).len(), 1);
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let p = Parser::new("{:w} {:d} {:f}", true).unwrap();
+        let result = p.parse("load 3 0.75").unwrap();
+        assert_eq!(result.into_vec(), vec!["load", "3", "0.75"]);
+    }
+
+    #[test]
+    fn test_ordinal_converter() {
+        let p = Parser::new("finished {:ord}", true).unwrap();
+        for (text, expected) in [
+            ("1st", 1),
+            ("2nd", 2),
+            ("3rd", 3),
+            ("11th", 11),
+            ("22nd", 22),
+        ] {
+            let result = p.parse(&format!("finished {}", text)).unwrap();
+            assert_eq!(*result.get::<i64>(0).unwrap(), expected);
+        }
+
+        // The suffix has to agree with the number
+        assert!(p.parse("finished 11st").is_none());
+        assert!(p.parse("finished 3th").is_none());
+
+        // Any text can be reinterpreted, so non-ordinals fail without panicking
+        for text in ["", "th", "\u{e9}a", "1\u{e9}"] {
+            assert!(matches!(
+                OrdinalConverter.convert(text),
+                Err(ParseError::TypeConversionFailed)
+            ));
+        }
+        let result = parse("{:w}", "\u{e9}a").unwrap();
+        assert_eq!(result.reinterpret::<i64>("0", &OrdinalConverter), None);
+    }
+
+    #[test]
+    fn test_field_default() {
+        let p = Parser::new("listen {host:w}:{port:d=8080}", true).unwrap();
+
+        let result = p.parse("listen localhost:9000").unwrap();
+        assert_eq!(*result.named::<i64>("port").unwrap(), 9000);
+
+        let result = p.parse("listen localhost:").unwrap();
+        assert_eq!(*result.named::<i64>("port").unwrap(), 8080);
+        assert_eq!(*result.named::<String>("host").unwrap(), "localhost");
+
+        // The default must be a valid value of the field's type
+        assert!(Parser::new("{port:d=http}", true).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_nested_map() {
+        let p = Parser::new("{user.name:w} ({user.id:d}) {action}", true).unwrap();
+        let result = p.parse("alice (42) login").unwrap();
+        assert_eq!(*result.named::<i64>("user.id").unwrap(), 42);
+
+        let nested = result.nested_map();
+        assert_eq!(nested["user"]["name"], "alice");
+        assert_eq!(nested["user"]["id"], 42);
+        assert_eq!(nested["action"], "login");
+        assert_eq!(nested.as_object().unwrap().len(), 2);
+        assert_eq!(nested["user"].as_object().unwrap().len(), 2);
+
+        // A field that is also a prefix gives way to the nested object
+        let p = Parser::new("{user} {user.id:d}", true).unwrap();
+        let nested = p.parse("alice 42").unwrap().nested_map();
+        assert_eq!(nested["user"]["id"], 42);
+
+        // Leaves are typed like `to_json`'s values
+        let p = Parser::new("{a.b:d} {a.c:f}", true).unwrap();
+        let result = p.parse("1 2.5").unwrap();
+        assert_eq!(result.nested_map().to_string(), r#"{"a":{"b":1,"c":2.5}}"#);
+        assert_eq!(result.to_json(), r#"{"a.b":1,"a.c":2.5}"#);
+    }
+
+    #[test]
+    fn test_scientific_integers() {
+        let p = Parser::new("count={:d}", true).unwrap();
+        assert!(p.parse("count=1e3").is_none());
+
+        let p = Parser::builder("count={:d}")
+            .scientific_integers(true)
+            .build()
+            .unwrap();
+        assert_eq!(*p.parse("count=1e3").unwrap().get::<i64>(0).unwrap(), 1000);
+        assert_eq!(*p.parse("count=2.5E2").unwrap().get::<i64>(0).unwrap(), 250);
+        assert_eq!(*p.parse("count=-7").unwrap().get::<i64>(0).unwrap(), -7);
+        assert!(p.parse("count=1.5e0").is_none());
+        assert!(p.parse("count=1.5").is_none());
+    }
+
+    #[test]
+    fn test_findall_with_gaps() {
+        let p = Parser::new("{:d}", true).unwrap();
+        let segments = p.findall_with_gaps("a 1 b 2");
+        assert_eq!(segments.len(), 4);
+
+        let pieces: Vec<String> = segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => format!("text:{}", text),
+                Segment::Match(result) => format!("match:{}", result.get::<i64>(0).unwrap()),
+            })
+            .collect();
+        assert_eq!(pieces, vec!["text:a ", "match:1", "text: b ", "match:2"]);
+
+        assert!(p.findall_with_gaps("").is_empty());
+    }
+
+    #[test]
+    fn test_whitespace_sensitive_converters() {
+        #[derive(Debug, Clone)]
+        struct TokenConverter;
+        impl TypeConverter for TokenConverter {
+            convert_via_shared!();
+
+            fn convert_shared(
+                &self,
+                s: &str,
+            ) -> Result<Box<dyn std::any::Any + Send + Sync>, ParseError> {
+                Ok(Box::new(s.to_string()))
+            }
+            fn is_whitespace_sensitive(&self) -> bool {
+                false
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        struct TextConverter;
+        impl TypeConverter for TextConverter {
+            fn convert(&self, s: &str) -> Result<Box<dyn std::any::Any>, ParseError> {
+                Ok(Box::new(s.to_string()))
+            }
+        }
+
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert("tok".to_string(), Box::new(TokenConverter));
+        types.insert("text".to_string(), Box::new(TextConverter));
+        types.insert(
+            "chained".to_string(),
+            Box::new(ChainConverter::new(Box::new(TokenConverter), Ok)),
+        );
+
+        // A `w` field and an `S` field in one format
+        let p = Parser::new("{user:w} said {msg:S}", true).unwrap();
+        let result = p.parse("bob said hello there").unwrap();
+        assert_eq!(*result.named::<String>("user").unwrap(), "bob");
+        assert_eq!(*result.named::<String>("msg").unwrap(), "hello there");
+
+        // A pattern-less converter can still refuse whitespace
+        let p = Parser::new_with_types("{key:tok}:{value:S}", true, types.clone()).unwrap();
+        let result = p.parse("path:/usr/local bin").unwrap();
+        assert_eq!(*result.named::<String>("value").unwrap(), "/usr/local bin");
+        assert!(p.parse("a path:/usr").is_none());
+
+        // The hook alone decides how much text the same kind of field takes
+        let p = Parser::new_with_types("{key:tok}:", true, types.clone()).unwrap();
+        let result = p.search("a path:/usr").unwrap();
+        assert_eq!(*result.named::<String>("key").unwrap(), "path");
+        let p = Parser::new_with_types("{key:text}:", true, types.clone()).unwrap();
+        let result = p.search("a path:/usr").unwrap();
+        assert_eq!(*result.named::<String>("key").unwrap(), "a path");
+
+        // A chain keeps its inner converter's choice
+        let p = Parser::new_with_types("{key:chained}:", true, types).unwrap();
+        let result = p.search("a path:/usr").unwrap();
+        assert_eq!(*result.named::<String>("key").unwrap(), "path");
+    }
+
+    #[test]
+    fn test_absent_as_empty() {
+        let format = "id {a:d}{|}name {b:w}";
+
+        // By default a field from the other branch is absent
+        let p = Parser::new(format, true).unwrap();
+        let result = p.parse("name bob").unwrap();
+        assert!(result.named::<String>("a").is_none());
+        assert_eq!(*result.get::<String>(0).unwrap(), "bob");
+
+        let p = Parser::builder(format)
+            .case_sensitive(true)
+            .absent_as_empty(true)
+            .build()
+            .unwrap();
+        let result = p.parse("name bob").unwrap();
+        assert_eq!(*result.named::<String>("a").unwrap(), "");
+        assert_eq!(*result.get::<String>(0).unwrap(), "");
+        assert_eq!(*result.get::<String>(1).unwrap(), "bob");
+
+        let result = p.parse("id 7").unwrap();
+        assert_eq!(*result.named::<i64>("a").unwrap(), 7);
+        assert_eq!(*result.named::<String>("b").unwrap(), "");
+    }
+
+    #[test]
+    fn test_named_dotted_aliases() {
+        let p = Parser::new("{user.name:w} {user__id:d}", true).unwrap();
+        let result = p.parse("alice 42").unwrap();
+
+        assert_eq!(*result.named::<String>("user.name").unwrap(), "alice");
+        assert_eq!(*result.named::<String>("user__name").unwrap(), "alice");
+        assert_eq!(*result.named::<i64>("user.id").unwrap(), 42);
+        assert_eq!(*result.named::<i64>("user__id").unwrap(), 42);
+
+        // A wrong type is still `None`
+        assert!(result.named::<i64>("user__name").is_none());
+    }
+
+    #[test]
+    fn test_phone_converter() {
+        let p = Parser::new("tel: {:phone}", true).unwrap();
+        let result = p.parse("tel: +1 (555) 123-4567").unwrap();
+        assert_eq!(*result.get::<String>(0).unwrap(), "+15551234567");
+
+        let result = p.parse("tel: 5551234567").unwrap();
+        assert_eq!(*result.get::<String>(0).unwrap(), "5551234567");
+
+        let result = p.parse("tel: 555.123.4567").unwrap();
+        assert_eq!(*result.get::<String>(0).unwrap(), "5551234567");
+
+        assert!(p.parse("tel: (1) 2-3").is_none());
+
+        // Surrounding spaces are left out of the match
+        let p = Parser::new("{:phone}", true).unwrap();
+        let result = p.search("call 555 123 4567 now").unwrap();
+        assert_eq!(result.spans_of(0), Some((5, 17)));
+        assert_eq!(*result.get::<String>(0).unwrap(), "5551234567");
+    }
+
+    #[test]
+    fn test_field_spans() {
+        let p = Parser::new("{user:w} logged in from {ip} port {port:d=22}", true).unwrap();
+        let input = "alice logged in from 10.0.0.1 port 2222";
+        let result = p.parse(input).unwrap();
+        assert_eq!(result.named_span("user"), Some((0, 5)));
+        assert_eq!(result.spans_of(1), Some((21, 29)));
+        assert_eq!(result.named_span("port"), Some((35, 39)));
+        assert_eq!(result.spans(), &[Some((0, 5)), Some((21, 29)), Some((35, 39))]);
+
+        // A defaulted field took no text
+        let input = "alice logged in from 10.0.0.1 port ";
+        let result = p.parse(input).unwrap();
+        assert_eq!(*result.named::<i64>("port").unwrap(), 22);
+        assert_eq!(result.named_span("port"), None);
+        assert_eq!(result.spans()[2], None);
+        assert_eq!(result.field_slice(input, "port"), None);
+
+        // Neither did one from another branch, nor an unknown name
+        let p = Parser::builder("id {a:d}{|}name {b:w}")
+            .absent_as_empty(true)
+            .build()
+            .unwrap();
+        let result = p.parse("name bob").unwrap();
+        assert_eq!(result.named_span("a"), None);
+        assert_eq!(result.named_span("b"), Some((5, 8)));
+        assert_eq!(result.named_span("c"), None);
+        assert_eq!(result.field_slice("name bob", "a"), None);
+        assert_eq!(result.field_slice("name bob", "b"), Some("bob"));
+    }
+
+    #[test]
+    fn test_parse_first() {
+        let texts = ["user: bob", "uid: 1001", "uid: 1002"];
+        let (index, result) = parse_first("uid: {:d}", &texts).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(*result.get::<i64>(0).unwrap(), 1001);
+
+        assert!(parse_first("gid: {:d}", &texts).is_none());
+    }
+
+    #[test]
+    fn test_radix_converters() {
+        let p = Parser::new("addr={:x} mode={:o} flags={:b}", true).unwrap();
+        let result = p.parse("addr=0xFF mode=0755 flags=1010").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), 255);
+        assert_eq!(*result.get::<i64>(1).unwrap(), 0o755);
+        assert_eq!(*result.get::<i64>(2).unwrap(), 10);
+
+        // Prefixes are optional and case-insensitive
+        let result = p.parse("addr=1f4 mode=0O17 flags=0B11").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), 500);
+        assert_eq!(*result.get::<i64>(1).unwrap(), 15);
+        assert_eq!(*result.get::<i64>(2).unwrap(), 3);
+
+        assert_eq!(*parse("{:x}", "-0x10").unwrap().get::<i64>(0).unwrap(), -16);
+        assert!(parse("{:b}", "102").is_none());
+        assert!(matches!(
+            HexConverter.convert("0x1ffffffffffffffff"),
+            Err(ParseError::IntegerOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_debug_match_divergence() {
+        let p = Parser::new("{date:ti} {level:level} [{module:w}] {pid:d}: {msg}", true).unwrap();
+
+        // Input that stops short after the module
+        assert_eq!(
+            p.debug_match("2024-12-27 INFO [net]"),
+            MatchDiagnosis::Diverged { matched_fields: 3 }
+        );
+        // Input that goes wrong at the pid
+        assert_eq!(
+            p.debug_match("2024-12-27 INFO [net] abc: started"),
+            MatchDiagnosis::Diverged { matched_fields: 3 }
+        );
+        assert_eq!(
+            p.debug_match("2024-12-27 INFO [net] 12: started"),
+            MatchDiagnosis::Matched
+        );
+        assert_eq!(p.debug_match("yesterday"), MatchDiagnosis::NoMatch);
+    }
+
+    #[test]
+    fn test_datetime_keep_offset() {
+        use chrono::{DateTime, FixedOffset};
+
+        // By default the offset is applied and dropped
+        let result = parse("{:ti}", "2024-12-27T19:57:55+05:00").unwrap();
+        let naive: &NaiveDateTime = result.get(0).unwrap();
+        assert_eq!(naive.to_string(), "2024-12-27 14:57:55");
+
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert(
+            "tio".to_string(),
+            Box::new(DateTimeConverter::new("ti", true)),
+        );
+        types.insert(
+            "teo".to_string(),
+            Box::new(DateTimeConverter::new("te", true)),
+        );
+        let p = Parser::new_with_types("{:tio} / {:teo}", true, types).unwrap();
+
+        let result = p
+            .parse("2024-12-27T19:57:55+05:00 / Fri, 27 Dec 2024 19:57:55 -0800")
+            .unwrap();
+        let iso: &DateTime<FixedOffset> = result.get(0).unwrap();
+        assert_eq!(iso.to_rfc3339(), "2024-12-27T19:57:55+05:00");
+        let email: &DateTime<FixedOffset> = result.get(1).unwrap();
+        assert_eq!(email.offset().local_minus_utc(), -8 * 3600);
+
+        // Input without an offset is still a NaiveDateTime
+        let result = p
+            .parse("2024-12-27T19:57:55 / Fri, 27 Dec 2024 19:57:55 +0000")
+            .unwrap();
+        assert!(result.get::<NaiveDateTime>(0).is_some());
+    }
+
+    #[test]
+    fn test_quoted_field_names() {
+        let p = Parser::new(r#"{"full name":w} {"x=[a]"} {"age (years)":d}"#, true).unwrap();
+        let result = p.parse("Alice b 30").unwrap();
+        assert_eq!(*result.named::<String>("full name").unwrap(), "Alice");
+        assert_eq!(*result.named::<String>("x=[a]").unwrap(), "b");
+        assert_eq!(*result.named::<i64>("age (years)").unwrap(), 30);
+
+        assert!(Parser::new(r#"{"unterminated:w}"#, true).is_err());
+        assert!(Parser::new(r#"{"":w}"#, true).is_err());
+        assert!(Parser::new(r#"{"name"x:w}"#, true).is_err());
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64_converter() {
+        let p = Parser::new("token={:b64};", true).unwrap();
+        let result = p.parse("token=aGVsbG8gd29ybGQ=;").unwrap();
+        assert_eq!(result.get::<Vec<u8>>(0).unwrap(), b"hello world");
+
+        let result = p.parse("token=TWFu;").unwrap();
+        assert_eq!(result.get::<Vec<u8>>(0).unwrap(), b"Man");
+
+        // Wrong length or stray bits are rejected
+        assert!(p.parse("token=aGVsbG8;").is_none());
+        assert!(matches!(
+            Base64Converter.convert("TWF="),
+            Err(ParseError::TypeConversionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_untyped_fields_are_strings() {
+        let p = Parser::new("{} sent {:d} bytes to {} in {:f}s", true).unwrap();
+        let result = p.parse("alpha sent 512 bytes to beta in 0.25s").unwrap();
+        assert_eq!(*result.get::<String>(0).unwrap(), "alpha");
+        assert_eq!(*result.get::<i64>(1).unwrap(), 512);
+        assert_eq!(*result.get::<String>(2).unwrap(), "beta");
+        assert_eq!(*result.get::<f64>(3).unwrap(), 0.25);
+    }
+
+    #[test]
+    fn test_case_modifiers() {
+        let p = Parser::new("{method:w:upper} {path} {host:w:lower}", true).unwrap();
+        let result = p.parse("get /index.html Example").unwrap();
+        assert_eq!(*result.named::<String>("method").unwrap(), "GET");
+        assert_eq!(*result.named::<String>("path").unwrap(), "/index.html");
+        assert_eq!(*result.named::<String>("host").unwrap(), "example");
+        assert_eq!(result.into_vec(), vec!["GET", "/index.html", "example"]);
+
+        // The modified text is what gets converted
+        let p = Parser::new("{:level:lower}", true).unwrap();
+        assert_eq!(
+            *p.parse("WARN").unwrap().get::<LogLevel>(0).unwrap(),
+            LogLevel::Warn
+        );
+
+        assert!(Parser::new("{name:w:title}", true).is_err());
+    }
+
+    #[test]
+    fn test_sized_fields() {
+        // Fixed-column records
+        let p = Parser::new("{id:4d}{code:3}{name:5w}", true).unwrap();
+        let result = p.parse("0042ABCalice").unwrap();
+        assert_eq!(*result.named::<i64>("id").unwrap(), 42);
+        assert_eq!(*result.named::<String>("code").unwrap(), "ABC");
+        assert_eq!(*result.named::<String>("name").unwrap(), "alice");
+        assert!(p.parse("42ABCalice").is_none());
+
+        let p = Parser::new("{:.2f}", true).unwrap();
+        assert_eq!(*p.parse("3.14").unwrap().get::<f64>(0).unwrap(), 3.14);
+        assert!(p.parse("3.1").is_none());
+
+        let p = Parser::new("[{:6.2f}]", true).unwrap();
+        assert_eq!(*p.parse("[-12.50]").unwrap().get::<f64>(0).unwrap(), -12.5);
+
+        let p = Parser::new("{:2x}{:2x}", true).unwrap();
+        let result = p.parse("ff10").unwrap();
+        assert_eq!(*result.get::<i64>(0).unwrap(), 255);
+        assert_eq!(*result.get::<i64>(1).unwrap(), 16);
+
+        assert!(Parser::new("{id:0d}", true).is_err());
+        assert!(Parser::new("{id:4.d}", true).is_err());
+        assert!(Parser::new("{when:4ti}", true).is_err());
+
+        // A registered type key that starts with digits is not a size
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert("2fa".to_string(), Box::new(YesNoConverter));
+        let p = Parser::new_with_types("otp {:2fa}", true, types).unwrap();
+        assert_eq!(p.parse("otp yes").unwrap().get::<bool>(0), Some(&true));
+    }
+
+    #[test]
+    fn test_parser_cache() {
+        // Other tests share the cache, so only check what this test can see
+        let format = "cache test {:d} of {:d}";
+        for i in 0..3 {
+            let result = parse(format, &format!("cache test {} of 3", i)).unwrap();
+            assert_eq!(*result.get::<i64>(0).unwrap(), i);
+        }
+        assert!(Parser::cache_len() >= 1);
+        assert_eq!(
+            findall(format, "cache test 1 of 2, cache test 2 of 2").len(),
+            2
+        );
+
+        Parser::clear_cache();
+        assert!(search(format, "xx cache test 5 of 6").is_some());
+
+        // Invalid formats are not cached and still fail
+        assert!(parse("{unclosed", "x").is_none());
+
+        // Formats built on the fly don't grow the cache without bound
+        for i in 0..300 {
+            assert!(parse(&format!("bounded {} {{:d}}", i), &format!("bounded {} 7", i)).is_some());
+        }
+        assert!(Parser::cache_len() <= 256);
+    }
+
+    #[test]
+    fn test_range_converter() {
+        let p = Parser::new("ports {:range}", true).unwrap();
+        let result = p.parse("ports 5..10").unwrap();
+        assert_eq!(*result.get::<std::ops::Range<i64>>(0).unwrap(), 5..10);
+
+        let result = p.parse("ports 8000-8080").unwrap();
+        assert_eq!(*result.get::<std::ops::Range<i64>>(0).unwrap(), 8000..8080);
+
+        // Minus signs on the bounds
+        let result = p.parse("ports -5--1").unwrap();
+        assert_eq!(*result.get::<std::ops::Range<i64>>(0).unwrap(), -5..-1);
+        let result = p.parse("ports -3..4").unwrap();
+        assert_eq!(*result.get::<std::ops::Range<i64>>(0).unwrap(), -3..4);
+
+        assert!(p.parse("ports 10..5").is_none());
+
+        // Arbitrary text fails to convert rather than panicking
+        for text in ["", "-", "\u{e9}-5", "5-\u{e9}"] {
+            assert!(matches!(
+                RangeConverter.convert(text),
+                Err(ParseError::TypeConversionFailed)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_field_references() {
+        let p = Parser::new("<{open:w}>{body}</{close=open}>", true).unwrap();
+        let result = p.parse("<b>bold</b>").unwrap();
+        assert_eq!(*result.named::<String>("open").unwrap(), "b");
+        assert_eq!(*result.named::<String>("body").unwrap(), "bold");
+        assert_eq!(*result.named::<String>("close").unwrap(), "b");
+
+        assert!(p.parse("<b>bold</i>").is_none());
+        assert_eq!(p.findall("<i>x</i> <b>y</u> <u>z</u>").len(), 2);
+
+        // `search` passes over a mismatched first pair
+        let result = p.search("<x>a</y> <u>z</u>").unwrap();
+        assert_eq!(*result.named::<String>("open").unwrap(), "u");
+        assert_eq!(*result.named::<String>("body").unwrap(), "z");
+        assert!(p.search("<x>a</y>").is_none());
+
+        // The reference takes the earlier field's type
+        let p = Parser::new("{a:d} == {b=a}", true).unwrap();
+        assert_eq!(*p.parse("42 == 42").unwrap().named::<i64>("b").unwrap(), 42);
+        assert!(p.parse("42 == 43").is_none());
+
+        // It has to refer to an earlier field
+        assert!(Parser::new("{b=a} {a:d}", true).is_err());
+    }
+
+    #[test]
+    fn test_finditer() {
+        let p = Parser::new("[{:d}]", true).unwrap();
+        let text = "[1] [2] [x] [3] [4]";
+
+        let first_two: Vec<i64> = p
+            .finditer(text)
+            .take(2)
+            .map(|r| *r.get::<i64>(0).unwrap())
+            .collect();
+        assert_eq!(first_two, vec![1, 2]);
+
+        let below_four = p
+            .finditer(text)
+            .take_while(|r| *r.get::<i64>(0).unwrap() < 4)
+            .count();
+        assert_eq!(below_four, 3);
+        assert_eq!(p.finditer(text).count(), p.findall(text).len());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_findall_jsonl() {
+        let p = Parser::new("{user:w} took {:f}s ({status})", true).unwrap();
+        let text = "alice took 1.5s (ok)\nbob took 2s (said \"no\")\n";
+        let jsonl = p.findall_jsonl(text);
+
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], r#"{"user":"alice","1":1.5,"status":"ok"}"#);
+        assert_eq!(lines[1], r#"{"user":"bob","1":2.0,"status":"said \"no\""}"#);
+        assert!(jsonl.ends_with('\n'));
+
+        // Large floats keep their exponent
+        let result = p.parse("carol took 1e300s (slow)").unwrap();
+        assert_eq!(result.to_json(), r#"{"user":"carol","1":1e+300,"status":"slow"}"#);
+    }
+
+    #[test]
+    fn test_inline_regex_field() {
+        let result = parse(
+            r"Ticket {id:/[A-Z]{3}-\d+/} opened",
+            "Ticket ABC-123 opened",
+        )
+        .unwrap();
+        assert_eq!(result.named::<String>("id"), Some(&"ABC-123".to_string()));
+        assert!(parse(r"Ticket {id:/[A-Z]{3}-\d+/} opened", "Ticket AB-123 opened").is_none());
+
+        let parser = Parser::new(r"{path:/a\/b:\w+/}", true).unwrap();
+        let result = parser.parse("a/b:x1").unwrap();
+        assert_eq!(result.named::<String>("path"), Some(&"a/b:x1".to_string()));
+
+        assert!(matches!(
+            Parser::new(r"{id:/([A-Z]+)-\d+/}", true),
+            Err(ParseError::InvalidFormat)
+        ));
+        assert!(Parser::new(r"{id:/(?:[A-Z]+)-\d+/}", true).is_ok());
+        assert!(matches!(
+            Parser::new("{id:/[A-Z/}", true),
+            Err(ParseError::InvalidFormat)
+        ));
+        assert!(matches!(
+            Parser::new("{id:/[A-Z]", true),
+            Err(ParseError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_weekday_date() {
+        use chrono::NaiveDate;
+
+        let expected = NaiveDate::from_ymd_opt(2024, 12, 30).unwrap();
+        let result = parse("Report for {:dw}", "Report for Monday, 2024-12-30").unwrap();
+        assert_eq!(result.get::<NaiveDate>(0), Some(&expected));
+        let result = parse("Report for {:dw}", "Report for Mon 2024-12-30").unwrap();
+        assert_eq!(result.get::<NaiveDate>(0), Some(&expected));
+        let result = parse("Report for {:dw}", "Report for 2024-12-30").unwrap();
+        assert_eq!(result.get::<NaiveDate>(0), Some(&expected));
+
+        let parser = Parser::new("Report for {:dw}", true).unwrap();
+        assert!(parser.parse("Report for Tuesday, 2024-12-30").is_none());
+        assert!(parser.parse("Report for Someday, 2024-12-30").is_none());
+        assert!(parser.parse("Report for Monday, 2024-02-30").is_none());
+
+        // Non-ASCII digits and text fail instead of panicking
+        assert!(parser.parse("Report for Mon \u{663}024-12-30").is_none());
+        assert!(matches!(
+            WeekdayDateConverter.convert("x\u{e9}2024-12-3"),
+            Err(ParseError::TypeConversionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_try_parse_and_search() {
+        let parser = Parser::new("Value is {:d}", true).unwrap();
+        let result = parser.try_parse("Value is 42").unwrap();
+        assert_eq!(result.get::<i64>(0), Some(&42));
+        assert!(matches!(
+            parser.try_parse("Value was 42"),
+            Err(ParseError::NoMatch)
+        ));
+        assert!(matches!(
+            parser.try_parse("Value is 99999999999999999999"),
+            Err(ParseError::IntegerOverflow(_))
+        ));
+        assert!(parser.parse("Value is 99999999999999999999").is_none());
+
+        let result = parser.try_search("Note: Value is 7.").unwrap();
+        assert_eq!(result.get::<i64>(0), Some(&7));
+        assert!(matches!(
+            parser.try_search("nothing here"),
+            Err(ParseError::NoMatch)
+        ));
+        assert!(matches!(
+            parser.try_search("Note: Value is 99999999999999999999."),
+            Err(ParseError::IntegerOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_converter_registry() {
+        let port = ChainConverter::new(Box::new(IntConverter), |value| {
+            let n = *value.downcast::<i64>().unwrap();
+            let port = u16::try_from(n).map_err(|_| ParseError::TypeConversionFailed)?;
+            Ok(Box::new(port))
+        });
+        let mut types: HashMap<String, Box<dyn TypeConverter>> = HashMap::new();
+        types.insert("port".to_string(), Box::new(port));
+        let registry = ConverterRegistry::new(types);
+
+        let listens = Parser::new_with_registry("listening on {:port}", true, &registry).unwrap();
+        let requests =
+            Parser::new_with_registry("GET {path} on {:port} x{n:d}", true, &registry).unwrap();
+
+        let result = listens.parse("listening on 8080").unwrap();
+        assert_eq!(result.get::<u16>(0), Some(&8080));
+        assert!(listens.parse("listening on 70000").is_none());
+
+        let result = requests.parse("GET /index on 443 x2").unwrap();
+        assert_eq!(result.named::<String>("path"), Some(&"/index".to_string()));
+        assert_eq!(result.get::<u16>(1), Some(&443));
+        assert_eq!(result.named::<i64>("n"), Some(&2));
+    }
+
+    #[test]
+    fn test_repeated_field_name() {
+        let p = Parser::new("{tag} {tag}", true).unwrap();
+        let result = p.parse("x x").unwrap();
+        assert_eq!(result.named::<String>("tag"), Some(&"x".to_string()));
+        assert_eq!(result.into_vec().len(), 1);
+        assert!(p.parse("x y").is_none());
+
+        // The second occurrence takes no position of its own
+        let p = Parser::new("{tag} {tag} {}", true).unwrap();
+        let result = p.parse("x x y").unwrap();
+        assert_eq!(result.get::<String>(1), Some(&"y".to_string()));
+        assert_eq!(result.named::<String>("1"), Some(&"y".to_string()));
+        assert!(result.named::<String>("2").is_none());
+
+        let p = Parser::new("<{tag:w}>{body}</{tag}>", true).unwrap();
+        let result = p.parse("<b>bold</b>").unwrap();
+        assert_eq!(result.named::<String>("body"), Some(&"bold".to_string()));
+        assert!(p.parse("<b>bold</i>").is_none());
+        assert_eq!(p.findall("<b>x</i> <i>y</i>").len(), 1);
+
+        // `search` moves past a candidate whose tags differ, like `findall`
+        let result = p.search("<a>x</b> <c>y</c>").unwrap();
+        assert_eq!(result.named::<String>("body"), Some(&"y".to_string()));
+        assert_eq!(result.match_span(), (9, 17));
+
+        // Matching is not retried with the fields split differently
+        let p = Parser::new("{tag} {tag}", true).unwrap();
+        assert!(p.parse("a b a b").is_none());
+
+        let p = Parser::new("{n:d}-{n:d}", false).unwrap();
+        assert_eq!(p.parse("7-7").unwrap().named::<i64>("n"), Some(&7));
+        assert!(p.parse("7-8").is_none());
+
+        assert!(matches!(
+            Parser::new("{n:d}-{n:w}", true),
+            Err(ParseError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_literal_minus_before_number() {
+        // A literal `-` before a signed field and a negative value both work
+        let p = Parser::new("temp:{:d}", true).unwrap();
+        assert_eq!(p.parse("temp:-5").unwrap().get::<i64>(0), Some(&-5));
+        let p = Parser::new("temp:-{:d}", true).unwrap();
+        assert_eq!(p.parse("temp:-5").unwrap().get::<i64>(0), Some(&5));
+        assert_eq!(p.parse("temp:--5").unwrap().get::<i64>(0), Some(&-5));
+
+        let p = Parser::new("{lo:d}-{hi:d}", true).unwrap();
+        let result = p.parse("-5--3").unwrap();
+        assert_eq!(result.named::<i64>("lo"), Some(&-5));
+        assert_eq!(result.named::<i64>("hi"), Some(&-3));
+
+        // `:unsigned` leaves every sign to the literal text
+        let p = Parser::new("{lo:d}-{hi:d:unsigned}", true).unwrap();
+        let result = p.parse("-5-3").unwrap();
+        assert_eq!(result.named::<i64>("lo"), Some(&-5));
+        assert_eq!(result.named::<i64>("hi"), Some(&3));
+        assert!(p.parse("-5--3").is_none());
+
+        let p = Parser::new("offset -{n:d:unsigned}", true).unwrap();
+        assert_eq!(p.parse("offset -12").unwrap().named::<i64>("n"), Some(&12));
+        assert!(p.parse("offset --12").is_none());
+
+        assert!(matches!(
+            Parser::new("{:w:unsigned}", true),
+            Err(ParseError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_search_from_and_findall_in() {
+        let p = Parser::new("id={:d}", true).unwrap();
+        let text = "id=1 id=22 id=333 id=4";
+
+        let result = p.search_from(text, 3).unwrap();
+        assert_eq!(result.get::<i64>(0), Some(&22));
+        assert_eq!(result.match_span(), (5, 10));
+        assert!(p.search_from(text, 19).is_none());
+
+        let results = p.findall_in(text, 5, 17);
+        let values: Vec<i64> = results.iter().map(|r| *r.get::<i64>(0).unwrap()).collect();
+        assert_eq!(values, vec![22, 333]);
+        assert_eq!(results[1].spans_of(0), Some((14, 17)));
+        // The region end cuts the last number short
+        assert_eq!(p.findall_in(text, 11, 16)[0].get::<i64>(0), Some(&33));
+
+        assert!(p.findall_in(text, 10, 5).is_empty());
+        assert!(p.findall_in(text, 0, 100).is_empty());
+        assert!(p.search_from(text, 100).is_none());
+        assert!(p.search_from("é id=1", 1).is_none());
+        assert_eq!(p.search_from("é id=1", 2).unwrap().get::<i64>(0), Some(&1));
+
+        // Like `search`, a first match that fails to convert ends the search
+        let p = Parser::new("{:d}", true).unwrap();
+        assert!(p.search("99999999999999999999 5").is_none());
+        assert!(p.search_from("99999999999999999999 5", 0).is_none());
+        assert_eq!(p.search_from("99999999999999999999 5", 20).unwrap().get::<i64>(0), Some(&5));
+
+        // Offsets are into the text as given, even when its whitespace is collapsed
+        let p = Parser::builder("{:w} {:d}")
+            .normalize_whitespace(true)
+            .build()
+            .unwrap();
+        let text = "a     1 b 2";
+        assert_eq!(p.search_from(text, 8).unwrap().get::<String>(0), Some(&"b".to_string()));
+        assert_eq!(p.findall_in(text, 0, 7).len(), 1);
+        assert_eq!(p.findall_in(text, 2, 11).len(), 1);
+        assert!(p.search_from(text, 12).is_none());
+    }
+
+    #[test]
+    fn test_sub_records() {
+        let p = Parser::new("config {name:w} [{entries:[{k:w}={v:w}]}]", true).unwrap();
+        let text = "config db [host=local, port=5432]";
+        let result = p.parse(text).unwrap();
+        assert_eq!(result.named::<String>("name"), Some(&"db".to_string()));
+
+        let entries = result.records("entries").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].named::<String>("k"), Some(&"host".to_string()));
+        assert_eq!(entries[0].named::<String>("v"), Some(&"local".to_string()));
+        assert_eq!(entries[1].named::<String>("k"), Some(&"port".to_string()));
+        assert_eq!(entries[1].field_slice(text, "v"), Some("5432"));
+        assert_eq!(
+            p.field_type_id("entries"),
+            Some(std::any::TypeId::of::<Vec<ParseResult>>())
+        );
+
+        let result = p.parse("config db []").unwrap();
+        assert_eq!(result.records("entries").map(<[ParseResult]>::len), Some(0));
+        assert!(result.records("name").is_none());
+
+        let p = Parser::new("{xs:[{:d}]}", true).unwrap();
+        let result = p.parse("1, 2, 3").unwrap();
+        assert_eq!(result.records("xs").map(<[ParseResult]>::len), Some(3));
+        #[cfg(feature = "serde")]
+        assert_eq!(result.to_json(), r#"{"xs":[{"0":1},{"0":2},{"0":3}]}"#);
+
+        assert!(matches!(
+            Parser::new("{xs:[{:d}}", true),
+            Err(ParseError::InvalidFormat)
+        ));
+        assert!(matches!(
+            Parser::new("{xs:[plain]}", true),
+            Err(ParseError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_letter_field() {
+        let result = parse("{name:l}{number:d}", "Gate7").unwrap();
+        assert_eq!(result.named::<String>("name"), Some(&"Gate".to_string()));
+        assert_eq!(result.named::<i64>("number"), Some(&7));
+
+        assert!(parse("Room {name:l}", "Room 4B").is_none());
+        assert!(parse("Room {name:l}", "Room snake_case").is_none());
+        let result = parse("Room {name:l}", "Room Zürich").unwrap();
+        assert_eq!(result.named::<String>("name"), Some(&"Zürich".to_string()));
+    }
+
+    #[test]
+    fn test_try_parse_collect_errors() {
+        let p = Parser::new("{name:w} age={age:d} day={day:ord} at {t:ti}", true).unwrap();
+        let (result, errors) = p
+            .try_parse_collect_errors("bob age=99999999999999999999 day=2th at 2024-01-02T03:04:05")
+            .unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].0, 1);
+        assert!(matches!(errors[0].1, ParseError::IntegerOverflow(_)));
+        assert_eq!(errors[1].0, 2);
+        assert!(matches!(errors[1].1, ParseError::TypeConversionFailed));
+
+        // The other fields are converted, and failed ones keep their text
+        assert_eq!(result.named::<String>("name"), Some(&"bob".to_string()));
+        assert!(result.named::<chrono::NaiveDateTime>("t").is_some());
+        assert!(result.named::<i64>("age").is_none());
+        assert_eq!(result.named::<String>("day"), Some(&"2th".to_string()));
+
+        let (_, errors) = p
+            .try_parse_collect_errors("bob age=3 day=2nd at 2024-01-02T03:04:05")
+            .unwrap();
+        assert!(errors.is_empty());
+        assert!(matches!(
+            p.try_parse_collect_errors("nope"),
+            Err(ParseError::NoMatch)
+        ));
+    }
+
+    #[test]
+    fn test_float_scientific_and_signed() {
+        let value = |text: &str| parse("x={:f}", text).map(|r| *r.get::<f64>(0).unwrap());
+        assert_eq!(value("x=6.022e23"), Some(6.022e23));
+        assert_eq!(value("x=-3.5E-4"), Some(-3.5e-4));
+        assert_eq!(value("x=1e9"), Some(1e9));
+        assert_eq!(value("x=+.5"), Some(0.5));
+        assert_eq!(value("x=+1.0"), Some(1.0));
+        assert_eq!(value("x=2."), Some(2.0));
+        assert_eq!(value("x=42"), Some(42.0));
+        assert_eq!(value("x=-inf"), Some(f64::NEG_INFINITY));
+        assert!(value("x=NaN").unwrap().is_nan());
+        assert!(value("x=1e").is_none());
+        assert!(value("x=.").is_none());
+
+        // `inf` and `nan` only count as whole words
+        let result = search("{:f}", "information: 3.5").unwrap();
+        assert_eq!(result.get::<f64>(0), Some(&3.5));
+    }
 }